@@ -0,0 +1,138 @@
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+/// Where the REST API documentation lives in a copy of the docs-api site
+const DOCUMENTATION_PATH: &str = "rest-api/index.html";
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_MAGIC: &[u8] = b"ustar";
+
+/// Read documentation HTML from a file, unpacking it if needed
+///
+/// See [`decode`] for the kinds of file understood.
+pub fn read(path: &Path) -> io::Result<String> {
+    decode(fs::read(path)?)
+}
+
+/// Unpack documentation HTML
+///
+/// Accepts plain HTML, gzip compressed HTML, and zip or tar archives (gzipped
+/// or not) of the docs-api site. Archives are searched for
+/// `rest-api/index.html`, or failing that their only HTML file.
+pub fn decode(bytes: Vec<u8>) -> io::Result<String> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(&bytes[..]).read_to_end(&mut decompressed)?;
+        decode(decompressed)
+    } else if bytes.starts_with(ZIP_MAGIC) {
+        decode(unzip(bytes)?)
+    } else if bytes.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()) == Some(TAR_MAGIC) {
+        decode(untar(&bytes)?)
+    } else {
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn unzip(bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let name = locate(archive.file_names())?;
+    let mut contents = Vec::new();
+    archive.by_name(&name)?.read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+fn untar(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut names = Vec::new();
+    for entry in tar::Archive::new(bytes).entries()? {
+        names.push(entry?.path()?.to_string_lossy().into_owned());
+    }
+    let name = locate(names.iter().map(String::as_str))?;
+
+    // Entries can only be read in order, so the archive is walked again
+    for entry in tar::Archive::new(bytes).entries()? {
+        let mut entry = entry?;
+        if entry.path()?.to_string_lossy() == name {
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            return Ok(contents);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} was listed in the archive but could not be read", name),
+    ))
+}
+
+/// Pick the documentation out of an archive's file names
+fn locate<'a>(names: impl Iterator<Item = &'a str>) -> io::Result<String> {
+    let html: Vec<&str> = names
+        .filter(|name| name.ends_with(".html") || name.ends_with(".html.gz"))
+        .collect();
+
+    if let Some(name) = html
+        .iter()
+        .find(|name| name.trim_end_matches(".gz").ends_with(DOCUMENTATION_PATH))
+    {
+        return Ok(name.to_string());
+    }
+    match html.as_slice() {
+        [name] => Ok(name.to_string()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "archive has no {} and {} other HTML files",
+                DOCUMENTATION_PATH,
+                html.len()
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, locate};
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const HTML: &str = "<h1>Keycloak Admin REST API</h1>";
+
+    #[test]
+    fn passes_html_through() {
+        assert_eq!(decode(HTML.into()).unwrap(), HTML);
+    }
+
+    #[test]
+    fn decompresses_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(HTML.as_bytes()).unwrap();
+
+        assert_eq!(decode(encoder.finish().unwrap()).unwrap(), HTML);
+    }
+
+    #[test]
+    fn finds_the_rest_api_documentation() {
+        let names = [
+            "docs-api/23.0.7/javadocs/index.html",
+            "docs-api/23.0.7/rest-api/index.html",
+            "docs-api/23.0.7/rest-api/style.css",
+        ];
+
+        assert_eq!(
+            locate(names.iter().copied()).unwrap(),
+            "docs-api/23.0.7/rest-api/index.html"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_only_html_file() {
+        let names = ["23.0.7.html", "LICENSE.txt"];
+
+        assert_eq!(locate(names.iter().copied()).unwrap(), "23.0.7.html");
+        assert!(locate(["a.html", "b.html"].iter().copied()).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Downloaded documentation, stored under the SHA-256 of its contents
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf) -> Self {
+        Cache { dir }
+    }
+
+    /// Where contents with the given checksum are stored
+    pub fn path(&self, sha256: &str) -> PathBuf {
+        self.dir.join("sha256").join(sha256)
+    }
+
+    pub fn contains(&self, sha256: &str) -> bool {
+        self.path(sha256).is_file()
+    }
+
+    /// Store contents, returning their checksum
+    pub fn insert(&self, contents: &[u8]) -> io::Result<String> {
+        let sha256 = sha256(contents);
+        let path = self.path(&sha256);
+        if !path.is_file() {
+            fs::create_dir_all(path.parent().expect("cache paths have a parent"))?;
+            // Written aside and renamed so an interrupted download is never cached
+            let partial = path.with_extension("partial");
+            fs::write(&partial, contents)?;
+            fs::rename(&partial, &path)?;
+        }
+        Ok(sha256)
+    }
+
+    /// Read the contents with the given checksum, verifying they haven't changed
+    pub fn get(&self, sha256: &str) -> io::Result<Vec<u8>> {
+        let path = self.path(sha256);
+        let contents = fs::read(&path)?;
+        let actual = self::sha256(&contents);
+        if actual != sha256 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} has checksum {}, expected {}",
+                    path.display(),
+                    actual,
+                    sha256
+                ),
+            ));
+        }
+        Ok(contents)
+    }
+}
+
+pub fn sha256(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// Where each version's documentation was fetched from and its checksum
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checksum {
+    pub url: String,
+    pub sha256: String,
+}
+
+/// The checksums of every fetched version, keyed by version
+///
+/// Committed alongside the manifest so the cache can be verified and
+/// repopulated.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Checksums(pub BTreeMap<String, Checksum>);
+
+impl Checksums {
+    /// Load checksums, treating a missing file as empty
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Default::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, format!("{}\n", serde_json::to_string_pretty(self)?))?;
+        Ok(())
+    }
+
+    pub fn get(&self, version: &str) -> Option<&Checksum> {
+        self.0.get(version)
+    }
+
+    pub fn insert(&mut self, version: String, checksum: Checksum) {
+        self.0.insert(version, checksum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha256, Cache};
+    use std::fs;
+
+    fn cache(name: &str) -> Cache {
+        let dir = std::env::temp_dir().join(format!(
+            "keycloak-openapi-transformer-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        Cache::new(dir)
+    }
+
+    #[test]
+    fn stores_contents_by_checksum() {
+        let cache = cache("stores");
+        let checksum = cache.insert(b"<h1>Keycloak</h1>").unwrap();
+
+        assert_eq!(checksum, sha256(b"<h1>Keycloak</h1>"));
+        assert!(cache.contains(&checksum));
+        assert_eq!(cache.get(&checksum).unwrap(), b"<h1>Keycloak</h1>");
+    }
+
+    #[test]
+    fn detects_modified_contents() {
+        let cache = cache("modified");
+        let checksum = cache.insert(b"<h1>Keycloak</h1>").unwrap();
+        fs::write(cache.path(&checksum), b"<h1>Changed</h1>").unwrap();
+
+        assert!(cache.get(&checksum).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+use keycloak_openapi_transformer::archive;
+use keycloak_openapi_transformer::formats;
+use keycloak_openapi_transformer::ordering::Ordering;
+use keycloak_openapi_transformer::report::ReportFormat;
+use keycloak_openapi_transformer::required::RequiredOverrides;
+use keycloak_openapi_transformer::timestamps;
+use keycloak_openapi_transformer::{
+    servers::ServerOptions, Diagnostics, DiagnosticsFormat, Options, OutputFormat,
+};
+
+/// Transform Keycloak's HTML REST API documentation into OpenAPI definitions
+///
+/// Without a subcommand the `transform` arguments are accepted directly, so
+/// `keycloak-openapi-transformer < 23.0.7.html > 23.0.7.json` keeps working.
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub transform: TransformArgs,
+}
+
+impl Cli {
+    /// The subcommand to run, defaulting to `transform`
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Transform(self.transform))
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Transform one HTML document into an OpenAPI definition
+    Transform(TransformArgs),
+    /// Regenerate the JSON and YAML definitions of every version in a manifest
+    Regenerate(RegenerateArgs),
+    /// Download versions' documentation into the cache the manifest names and their `html`
+    Fetch(FetchArgs),
+    /// Report what was dropped, defaulted or guessed while transforming one HTML document
+    Report(ReportArgs),
+    /// Rewrite a JSON definition as YAML, or the other way round, without transforming it.
+    /// Keeps definitions of versions with no documentation to regenerate from in step
+    Convert(ConvertArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct TransformArgs {
+    /// Documentation to read: HTML, or an OpenAPI definition in JSON or YAML to normalise.
+    /// May be gzipped or in a zip or tar archive of the docs site.
+    /// May be repeated to merge several pages into one definition.
+    /// Standard input is read when absent or `-`
+    #[arg(short, long = "input", value_name = "PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    /// File to write the definition to. Standard output is used when absent or `-`
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Format of the definition, `json` or `yaml`.
+    /// Defaults to YAML for `.yml` and `.yaml` outputs, otherwise JSON
+    #[arg(short, long)]
+    pub format: Option<OutputFormat>,
+
+    #[command(flatten)]
+    pub options: OptionsArgs,
+
+    #[command(flatten)]
+    pub diagnostics: DiagnosticsArgs,
+}
+
+impl TransformArgs {
+    pub fn format(&self) -> OutputFormat {
+        self.format
+            .or_else(|| self.output.as_deref().and_then(OutputFormat::from_path))
+            .unwrap_or(OutputFormat::Json)
+    }
+}
+
+/// How documentation is transformed, shared by the commands that transform it
+#[derive(Debug, Args)]
+pub struct OptionsArgs {
+    /// Keycloak release the documentation describes, such as `23.0.7`.
+    /// Detected from the documentation when absent
+    #[arg(long, value_name = "VERSION")]
+    pub keycloak_version: Option<String>,
+
+    /// Server URL to list instead of the templated server. May be repeated
+    #[arg(long = "server-url", value_name = "URL")]
+    pub server_urls: Vec<String>,
+
+    /// Default of the templated server's `contextPath` variable.
+    /// Defaults to `/auth` before Keycloak 17 and empty from then on
+    #[arg(long, value_name = "PATH")]
+    pub context_path: Option<String>,
+
+    /// Published URL of the documentation. Operations and schemas link to
+    /// their section of it in `externalDocs`
+    #[arg(long, value_name = "URL")]
+    pub docs_url: Option<String>,
+
+    /// Order of paths, parameters, schemas, properties and tags: `canonical`
+    /// sorts them, `document` keeps the order the documentation lists them in
+    #[arg(long, default_value = "canonical")]
+    pub ordering: Ordering,
+
+    /// Layout of the documentation, `asciidoctor` (Keycloak 22.0.0 onwards) or
+    /// `swagger2markup` (Keycloak 5.0 to 12.0). Detected when absent
+    #[arg(long, value_name = "NAME")]
+    pub layout: Option<String>,
+
+    /// Require the properties needed to create a representation in the body
+    /// of the `POST` creating it, using the curated list of representations
+    #[arg(long)]
+    pub curated_required: bool,
+
+    /// Correct which properties are required using a JSON list of overrides
+    /// instead of the curated one, such as
+    /// `{"UserRepresentation": {"required": ["id"], "required_to_create": ["username"]}}`
+    #[arg(long, value_name = "PATH", conflicts_with = "curated_required")]
+    pub required_overrides: Option<PathBuf>,
+
+    /// Give string properties and parameters a format, such as `uri-reference`
+    /// for `redirectUris`, from their names and descriptions
+    #[arg(long)]
+    pub infer_formats: bool,
+
+    /// Mark `long` properties and parameters holding epoch-millisecond timestamps,
+    /// such as `createdTimestamp`, with an `x-unit` of `epoch-millis`, and date
+    /// parameters, such as `dateFrom`, with the `date` format
+    #[arg(long)]
+    pub mark_timestamps: bool,
+
+    /// Keep the documentation's API version as `info.version` instead of the release
+    #[arg(long)]
+    pub no_release_version: bool,
+
+    /// Leave out the `x-source-anchor` of operations and schemas
+    #[arg(long)]
+    pub no_source_anchors: bool,
+
+    /// Leave out the descriptions of schemas and their properties
+    #[arg(long)]
+    pub no_schema_descriptions: bool,
+
+    /// Leave out the properties the documentation marks required
+    #[arg(long)]
+    pub no_required_properties: bool,
+
+    /// List enumerated properties' values inline instead of giving them named enum schemas
+    #[arg(long)]
+    pub no_enum_schemas: bool,
+}
+
+impl OptionsArgs {
+    pub fn options(&self) -> Result<Options, Box<dyn std::error::Error>> {
+        let required_overrides = match &self.required_overrides {
+            Some(path) => Some(
+                RequiredOverrides::load(path)
+                    .map_err(|error| format!("could not load {}: {}", path.display(), error))?,
+            ),
+            None => self.curated_required.then(RequiredOverrides::curated),
+        };
+        Ok(Options {
+            keycloak_version: self.keycloak_version.clone(),
+            servers: ServerOptions {
+                urls: self.server_urls.clone(),
+                context_path: self.context_path.clone(),
+            },
+            docs_url: self.docs_url.clone(),
+            ordering: self.ordering,
+            layout: self.layout.clone(),
+            required_overrides,
+            format_rules: if self.infer_formats {
+                formats::default_rules()
+            } else {
+                Vec::new()
+            },
+            timestamp_rules: if self.mark_timestamps {
+                timestamps::default_rules()
+            } else {
+                Vec::new()
+            },
+            release_version: !self.no_release_version,
+            source_anchors: !self.no_source_anchors,
+            schema_descriptions: !self.no_schema_descriptions,
+            required_properties: !self.no_required_properties,
+            enum_schemas: !self.no_enum_schemas,
+        })
+    }
+}
+
+/// Where and how the diagnostics of a transform are written
+#[derive(Debug, Args)]
+pub struct DiagnosticsArgs {
+    /// Format of the diagnostics, `text` or `json`
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    pub diagnostics_format: DiagnosticsFormat,
+
+    /// File to write the diagnostics to. Standard error is used when absent or `-`
+    #[arg(long, value_name = "PATH")]
+    pub diagnostics_output: Option<PathBuf>,
+}
+
+impl DiagnosticsArgs {
+    pub fn write(&self, diagnostics: &Diagnostics) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_rendered(&self.diagnostics_format.render(diagnostics)?)
+    }
+
+    /// Write diagnostics that were already rendered in `diagnostics_format`
+    pub fn write_rendered(&self, rendered: &str) -> Result<(), Box<dyn std::error::Error>> {
+        match file(self.diagnostics_output.as_deref()) {
+            Some(path) => fs::write(path, rendered)?,
+            None => eprint!("{}", rendered),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct RegenerateArgs {
+    /// Manifest listing the versions and their HTML documentation
+    #[arg(short, long, value_name = "PATH", default_value = "versions.json")]
+    pub manifest: PathBuf,
+
+    /// Regenerate versions even when nothing they depend on has changed since they last were
+    #[arg(long, conflicts_with = "check")]
+    pub force: bool,
+
+    /// Verify the committed definitions match a fresh transform instead of writing them.
+    /// Exits with an error if any version differs
+    #[arg(long)]
+    pub check: bool,
+
+    /// Number of versions to transform at once. Defaults to the number of CPUs
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Exit with status 3 when any version reports warnings, as `transform` does.
+    /// Otherwise only definitions that fail validation change the exit status
+    #[arg(long)]
+    pub deny_warnings: bool,
+
+    #[command(flatten)]
+    pub diagnostics: DiagnosticsArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Manifest listing the versions to fetch
+    #[arg(short, long, value_name = "PATH", default_value = "versions.json")]
+    pub manifest: PathBuf,
+
+    /// Versions to fetch. Every version in the manifest is fetched when absent
+    #[arg(value_name = "VERSION")]
+    pub versions: Vec<String>,
+
+    /// URL documentation is published under, overriding the manifest's `docs_url`
+    #[arg(long, value_name = "URL")]
+    pub docs_url: Option<String>,
+
+    /// Download versions that are already cached, accepting changed checksums, and the license again.
+    /// Versions' `html` copies are rewritten with what was downloaded
+    #[arg(long)]
+    pub refresh: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Documentation to read: HTML, or an OpenAPI definition in JSON or YAML to normalise.
+    /// May be gzipped or in a zip or tar archive of the docs site.
+    /// May be repeated to merge several pages into one definition.
+    /// Standard input is read when absent or `-`
+    #[arg(short, long = "input", value_name = "PATH")]
+    pub inputs: Vec<PathBuf>,
+
+    /// File to write the report to. Standard output is used when absent or `-`
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Format of the report, `text` or `json`
+    #[arg(short, long, default_value = "text")]
+    pub format: ReportFormat,
+
+    #[command(flatten)]
+    pub options: OptionsArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    /// Definition to read, in JSON or YAML. Standard input is read when absent or `-`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// File to write the definition to. Standard output is used when absent or `-`
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Format to write, `json` or `yaml`.
+    /// Defaults to YAML for `.yml` and `.yaml` outputs, otherwise JSON
+    #[arg(short, long)]
+    pub format: Option<OutputFormat>,
+}
+
+impl ConvertArgs {
+    pub fn format(&self) -> OutputFormat {
+        self.format
+            .or_else(|| self.output.as_deref().and_then(OutputFormat::from_path))
+            .unwrap_or(OutputFormat::Json)
+    }
+}
+
+/// The file a path names, or `None` when it is missing or `-` for standard input/output
+fn file(path: Option<&Path>) -> Option<&Path> {
+    path.filter(|path| *path != Path::new("-"))
+}
+
+/// Read documentation, which may be compressed or inside a docs-api site archive
+pub fn read_input(path: Option<&Path>) -> io::Result<String> {
+    match file(path) {
+        Some(path) => archive::read(path),
+        None => {
+            let mut input = Vec::new();
+            io::stdin().read_to_end(&mut input)?;
+            archive::decode(input)
+        }
+    }
+}
+
+/// Read every input, or standard input when there are none
+pub fn read_inputs(paths: &[PathBuf]) -> io::Result<Vec<String>> {
+    if paths.is_empty() {
+        return Ok(vec![read_input(None)?]);
+    }
+    paths.iter().map(|path| read_input(Some(path))).collect()
+}
+
+pub fn write_output(path: Option<&Path>, contents: &str) -> io::Result<()> {
+    match file(path) {
+        Some(path) => fs::write(path, contents),
+        None => {
+            let stdout = io::stdout();
+            let mut handle = stdout.lock();
+            handle.write_all(contents.as_bytes())?;
+            handle.flush()
+        }
+    }
+}
@@ -2,34 +2,200 @@ use indexmap::IndexMap;
 use openapiv3::ObjectType;
 use openapiv3::Schema;
 use openapiv3::SchemaKind;
-use scraper::Selector;
-
-lazy_static! {
-    static ref SCHEMAS_SELECTOR: Selector =
-        Selector::parse("#models + .sectionbody > .sect2").unwrap();
-    static ref TITLE_SELECTOR: Selector = Selector::parse("h3").unwrap();
-    static ref ROW_SELECTOR: Selector = Selector::parse("table > tbody > tr").unwrap();
-    static ref PROPERTY_NAME_SELECTOR: Selector = Selector::parse("td:first-child strong").unwrap();
-    static ref TYPE_SELECTOR: Selector = Selector::parse("td:first-child + td").unwrap();
-}
 
+use heck::ToUpperCamelCase;
+
+use crate::ir;
+use crate::SOURCE_ANCHOR_EXTENSION;
+
+/// Emit an API's models as OpenAPI schemas
+///
+/// A property whose type lists its values, such as `enum (POSITIVE, NEGATIVE)`,
+/// or a string property whose format lists them, refers to a schema of its own
+/// so clients get a named enum type, as do the items of a list or set of one.
+/// It is named after the property, such as `Logic` for `logic`, unless a model
+/// or a different enum already has that name, when the model's name is put in
+/// front of it, followed by a number if that is taken too. A model the
+/// documentation leaves without properties, whose values are listed where a
+/// property refers to it, becomes an enum itself.
+/// Without `enum_schemas` only a type listing its values has them, inline, as
+/// the definitions published before had; values only listed as the format are
+/// left out, and the model without properties stays an empty object.
 pub fn parse_schemas(
-    document: &scraper::html::Html,
+    api: &ir::Api,
+    enum_schemas: bool,
 ) -> IndexMap<String, openapiv3::ReferenceOr<Schema>> {
-    document
-        .select(&SCHEMAS_SELECTOR)
-        .map(|section| {
-            (
-                section
-                    .select(&TITLE_SELECTOR)
-                    .next()
-                    .unwrap()
-                    .text()
-                    .collect(),
-                openapiv3::ReferenceOr::Item(parse_schema(section)),
-            )
+    let mut enums = Enums {
+        models: if enum_schemas {
+            enum_models(api)
+        } else {
+            IndexMap::new()
+        },
+        hoisted: IndexMap::new(),
+    };
+    let mut schemas: IndexMap<String, openapiv3::ReferenceOr<Schema>> = api
+        .models
+        .iter()
+        .map(|model| {
+            let schema = match enums.models.get(&model.name) {
+                Some(values) => Schema {
+                    schema_data: schema_data(model),
+                    schema_kind: SchemaKind::Type(enum_type(values)),
+                },
+                None => parse_schema(
+                    model,
+                    api,
+                    if enum_schemas { Some(&mut enums) } else { None },
+                ),
+            };
+            (model.name.clone(), openapiv3::ReferenceOr::Item(schema))
         })
-        .collect()
+        .collect();
+
+    for (name, values) in enums.hoisted {
+        // Hoisted enums are never named after a model, so none is replaced
+        schemas.insert(
+            name,
+            openapiv3::ReferenceOr::Item(Schema {
+                schema_data: Default::default(),
+                schema_kind: SchemaKind::Type(enum_type(&values)),
+            }),
+        );
+    }
+    schemas
+}
+
+/// The enum schemas that models' enumerated properties refer to
+struct Enums {
+    /// Models without properties whose values are listed where they're used
+    models: IndexMap<String, Vec<String>>,
+    /// Enums hoisted out of properties, by name
+    hoisted: IndexMap<String, Vec<String>>,
+}
+
+/// The models without properties that a property listing values refers to,
+/// with the first values listed for each
+fn enum_models(api: &ir::Api) -> IndexMap<String, Vec<String>> {
+    let mut models = IndexMap::new();
+    for property in api.models.iter().flat_map(|model| model.properties.iter()) {
+        if property.values.is_empty() {
+            continue;
+        }
+        let raw_type = apply_raw_type_patches(&property.raw_type);
+        let empty = api
+            .models
+            .iter()
+            .any(|model| model.name == raw_type && model.properties.is_empty());
+        if empty && !models.contains_key(raw_type) {
+            models.insert(raw_type.to_string(), property.values.clone());
+        }
+    }
+    models
+}
+
+/// The values of an enumerated type, such as `enum (POSITIVE, NEGATIVE)`
+pub fn enum_values(raw_type: &str) -> Option<Vec<String>> {
+    let values = raw_type
+        .trim()
+        .strip_prefix("enum")?
+        .trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    Some(
+        values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+fn enum_type(values: &[String]) -> openapiv3::Type {
+    openapiv3::Type::String(openapiv3::StringType {
+        enumeration: values.iter().cloned().map(Some).collect(),
+        ..Default::default()
+    })
+}
+
+/// The name of the schema a model's enumerated property refers to, adding it
+/// to the hoisted enums unless it is an enum model with the same values
+fn enum_name(
+    model: &ir::Model,
+    property: &ir::Property,
+    values: Vec<String>,
+    api: &ir::Api,
+    enums: &mut Enums,
+) -> String {
+    let short = property.name.to_upper_camel_case();
+    if enums.models.get(&short) == Some(&values) {
+        return short;
+    }
+    let prefixed = format!("{}{}", model.name, short);
+    let taken = |name: &str| {
+        api.models.iter().any(|model| model.name == name)
+            || matches!(enums.hoisted.get(name), Some(known) if known != &values)
+    };
+    let name = vec![short, prefixed.clone()]
+        .into_iter()
+        .chain((2..).map(|number| format!("{}{}", prefixed, number)))
+        .find(|name| !taken(name))
+        .expect("a numbered name is free");
+    enums.hoisted.insert(name.clone(), values);
+    name
+}
+
+/// The schema of a property with enumerated values, referring to a named enum
+fn hoist(
+    model: &ir::Model,
+    property: &ir::Property,
+    api: &ir::Api,
+    enums: &mut Enums,
+) -> Option<openapiv3::ReferenceOr<Box<Schema>>> {
+    let reference = |name: String| openapiv3::ReferenceOr::Reference {
+        reference: format!("#/components/schemas/{}", name),
+    };
+    let listed = Some(property.values.clone())
+        .filter(|values| !values.is_empty() && property.raw_type.eq_ignore_ascii_case("string"));
+    if let Some(values) = enum_values(&property.raw_type).or(listed) {
+        return Some(reference(enum_name(model, property, values, api, enums)));
+    }
+
+    let (item_type, unique_items) = collection_item_type(&property.raw_type)?;
+    let values = enum_values(item_type)?;
+    Some(openapiv3::ReferenceOr::Item(Box::new(Schema {
+        schema_data: Default::default(),
+        schema_kind: SchemaKind::Type(openapiv3::Type::Array(openapiv3::ArrayType {
+            items: Some(reference(enum_name(model, property, values, api, enums))),
+            min_items: None,
+            max_items: None,
+            unique_items,
+        })),
+    })))
+}
+
+/// The type of a list's or set's items, and whether they are unique
+fn collection_item_type(raw_type: &str) -> Option<(&str, bool)> {
+    if let Some(item_type) = raw_type.strip_prefix("List  of ") {
+        return Some((item_type, false));
+    }
+    if let Some(item_type) = raw_type
+        .strip_prefix("List[")
+        .and_then(|raw_type| raw_type.strip_suffix(']'))
+    {
+        return Some((item_type, false));
+    }
+    if let Some(item_type) = swagger2markup_item_type(raw_type) {
+        return Some((item_type, false));
+    }
+    raw_type
+        .strip_prefix("Set  of ")
+        .map(|item_type| (item_type, true))
+}
+
+/// The item type of a list written by swagger2markup, such as `string` in `< string > array`
+fn swagger2markup_item_type(raw_type: &str) -> Option<&str> {
+    raw_type.strip_prefix("< ")?.strip_suffix(" > array")
 }
 
 fn array_type(raw_type: &str) -> Option<openapiv3::Type> {
@@ -54,6 +220,7 @@ fn array_type(raw_type: &str) -> Option<openapiv3::Type> {
     if let Some(inner_type) = raw_type
         .strip_prefix("List[")
         .and_then(|raw_type| raw_type.strip_suffix(']'))
+        .or_else(|| swagger2markup_item_type(raw_type))
     {
         return Some(openapiv3::Type::Array(openapiv3::ArrayType {
             items: Some(parse_type_boxed(inner_type)),
@@ -98,6 +265,20 @@ fn map_type(raw_type: &str) -> Option<openapiv3::Type> {
         }));
     }
 
+    // swagger2markup writes maps as `< string, string > map`
+    if let Some((_, value_type)) = raw_type
+        .strip_prefix("< ")
+        .and_then(|raw_type| raw_type.strip_suffix(" > map"))
+        .and_then(|raw_type| raw_type.split_once(", "))
+    {
+        return Some(openapiv3::Type::Object(openapiv3::ObjectType {
+            additional_properties: Some(openapiv3::AdditionalProperties::Schema(Box::new(
+                parse_type(value_type),
+            ))),
+            ..Default::default()
+        }));
+    }
+
     if !raw_type.starts_with("Map  of ") && !raw_type.starts_with("Map[") {
         return None;
     }
@@ -129,23 +310,35 @@ fn wrapper(raw_type: &str) -> Option<openapiv3::Type> {
 }
 
 pub fn item_type(raw_type: &str) -> Option<openapiv3::Type> {
+    if let Some(values) = enum_values(raw_type) {
+        return Some(enum_type(&values));
+    }
+
     wrapper(raw_type)
         .or_else(|| array_type(raw_type))
         .or_else(|| set_type(raw_type))
         .or_else(|| map_type(raw_type))
         .or_else(|| match raw_type.to_lowercase().as_str() {
-            "integer" => Some(openapiv3::Type::Integer(openapiv3::IntegerType {
-                format: openapiv3::VariantOrUnknownOrEmpty::Item(openapiv3::IntegerFormat::Int32),
-                ..Default::default()
-            })),
-            "long" => Some(openapiv3::Type::Integer(openapiv3::IntegerType {
+            "integer" | "integer (int32)" => {
+                Some(openapiv3::Type::Integer(openapiv3::IntegerType {
+                    format: openapiv3::VariantOrUnknownOrEmpty::Item(
+                        openapiv3::IntegerFormat::Int32,
+                    ),
+                    ..Default::default()
+                }))
+            }
+            "long" | "integer (int64)" => Some(openapiv3::Type::Integer(openapiv3::IntegerType {
                 format: openapiv3::VariantOrUnknownOrEmpty::Item(openapiv3::IntegerFormat::Int64),
                 ..Default::default()
             })),
             "boolean" => Some(openapiv3::Type::Boolean {}),
-            "object" | "[object]" | "anytype" | "[anytype]" | "<<>>" => {
-                Some(openapiv3::Type::Object(Default::default()))
-            }
+            // Keycloak 23 documents values of any type as `oas_any_type_not_mapped`
+            "object"
+            | "[object]"
+            | "anytype"
+            | "[anytype]"
+            | "oas_any_type_not_mapped"
+            | "<<>>" => Some(openapiv3::Type::Object(Default::default())),
             "string" => Some(openapiv3::Type::String(Default::default())),
             "list" => Some(openapiv3::Type::Array(openapiv3::ArrayType {
                 items: Some(parse_type_boxed("Object")),
@@ -199,30 +392,46 @@ fn parse_type_boxed(raw_type: &str) -> openapiv3::ReferenceOr<Box<Schema>> {
     }
 }
 
-fn parse_schema(section: scraper::element_ref::ElementRef<'_>) -> Schema {
-    let properties = section
-        .select(&ROW_SELECTOR)
-        .map(|row| {
-            (
-                row.select(&PROPERTY_NAME_SELECTOR)
-                    .next()
-                    .unwrap()
-                    .text()
-                    .collect::<String>(),
-                parse_type_boxed(
-                    &row.select(&TYPE_SELECTOR)
-                        .next()
-                        .unwrap()
-                        .text()
-                        .collect::<String>(),
-                ),
-            )
+fn schema_data(model: &ir::Model) -> openapiv3::SchemaData {
+    let mut schema_data = openapiv3::SchemaData {
+        description: model.description.clone(),
+        ..Default::default()
+    };
+    if let Some(anchor) = &model.anchor {
+        schema_data
+            .extensions
+            .insert(SOURCE_ANCHOR_EXTENSION.to_string(), anchor.clone().into());
+    }
+    schema_data
+}
+
+fn parse_schema(model: &ir::Model, api: &ir::Api, mut enums: Option<&mut Enums>) -> Schema {
+    let properties = model
+        .properties
+        .iter()
+        .map(|property| {
+            let hoisted = enums
+                .as_deref_mut()
+                .and_then(|enums| hoist(model, property, api, enums));
+            let mut schema = hoisted.unwrap_or_else(|| parse_type_boxed(&property.raw_type));
+            // A reference can't be described in OpenAPI 3.0, its siblings are ignored
+            if let openapiv3::ReferenceOr::Item(schema) = &mut schema {
+                schema.schema_data.description = property.description.clone();
+            }
+            (property.name.clone(), schema)
         })
         .collect();
+
     Schema {
-        schema_data: Default::default(),
+        schema_data: schema_data(model),
         schema_kind: SchemaKind::Type(openapiv3::Type::Object(ObjectType {
             properties,
+            required: model
+                .properties
+                .iter()
+                .filter(|property| property.required)
+                .map(|property| property.name.clone())
+                .collect(),
             ..Default::default()
         })),
     }
@@ -230,21 +439,42 @@ fn parse_schema(section: scraper::element_ref::ElementRef<'_>) -> Schema {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_schemas;
-    use openapiv3::OpenAPI;
+    use super::{parse_schemas, parse_type};
+    use crate::diagnostics::Diagnostics;
+    use crate::fixture::{COMMITTED, JSON, STAGES, TRANSFORMED};
+    use crate::index::Index;
+    use crate::ir::{Api, Model, Property};
+    use crate::layout::Layout;
+    use crate::scrape::scrape;
+    use crate::SOURCE_ANCHOR_EXTENSION;
+    use openapiv3::ReferenceOr;
     use scraper::Html;
+    use serde_json::{json, Value};
 
-    const HTML: &str = include_str!("../../../keycloak/22.0.0.html");
-    const JSON: &str = include_str!("../../../keycloak/22.0.0.json");
-
+    /// Compare the schema, as rendered, with the committed definition's
     fn parse_schema_correctly(schema: &str) {
-        let openapi: OpenAPI = serde_json::from_str(JSON).expect("Could not deserialize example");
-        let components = openapi.components.expect("Couldn't deserialize components");
+        let committed: Value = serde_json::from_str(JSON).expect("Could not deserialize example");
+        let transformed = serde_json::to_value(&COMMITTED.spec).unwrap();
+        let render = |definition: &Value| {
+            let schema = &definition["components"]["schemas"][schema];
+            assert!(schema.is_object(), "{} is not defined", schema);
+            serde_json::to_string_pretty(schema).unwrap()
+        };
+
+        assert_eq!(render(&transformed), render(&committed));
+    }
 
-        assert_eq!(
-            components.schemas.get(schema),
-            parse_schemas(&Html::parse_document(HTML)).get(schema)
-        );
+    #[test]
+    fn records_the_section_a_schema_came_from() {
+        let schema = match STAGES.schemas.get("RealmRepresentation") {
+            Some(ReferenceOr::Item(schema)) => schema,
+            _ => panic!("Couldn't extract schema"),
+        };
+
+        assert!(schema
+            .schema_data
+            .extensions
+            .contains_key(SOURCE_ANCHOR_EXTENSION));
     }
 
     #[test]
@@ -301,4 +531,285 @@ mod tests {
     fn parses_schema_only_reference_array_as_expected() {
         parse_schema_correctly("AccessToken-Authorization");
     }
+
+    fn model(name: &str, properties: &[(&str, &str)]) -> Model {
+        Model {
+            name: name.into(),
+            properties: properties
+                .iter()
+                .map(|(name, raw_type)| Property {
+                    name: name.to_string(),
+                    raw_type: raw_type.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hoists_enumerated_properties() {
+        let api = Api {
+            models: vec![
+                model(
+                    "PolicyRepresentation",
+                    &[
+                        ("logic", "enum (POSITIVE, NEGATIVE)"),
+                        ("type", "enum (role, user)"),
+                    ],
+                ),
+                model(
+                    "ScopePermissionRepresentation",
+                    &[
+                        ("logic", "enum (POSITIVE, NEGATIVE)"),
+                        ("type", "enum (scope)"),
+                        ("roles", "List  of enum (admin, user)"),
+                    ],
+                ),
+                model("Type", &[]),
+            ],
+            ..Default::default()
+        };
+        let schemas = serde_json::to_value(parse_schemas(&api, true)).unwrap();
+
+        assert_eq!(
+            schemas["PolicyRepresentation"]["properties"],
+            json!({
+                "logic": { "$ref": "#/components/schemas/Logic" },
+                "type": { "$ref": "#/components/schemas/PolicyRepresentationType" },
+            })
+        );
+        assert_eq!(
+            schemas["ScopePermissionRepresentation"]["properties"],
+            json!({
+                "logic": { "$ref": "#/components/schemas/Logic" },
+                "type": { "$ref": "#/components/schemas/ScopePermissionRepresentationType" },
+                "roles": {
+                    "type": "array",
+                    "items": { "$ref": "#/components/schemas/Roles" }
+                },
+            })
+        );
+        assert_eq!(
+            schemas["Logic"],
+            json!({ "type": "string", "enum": ["POSITIVE", "NEGATIVE"] })
+        );
+        assert_eq!(
+            schemas["ScopePermissionRepresentationType"]["enum"],
+            json!(["scope"])
+        );
+        assert_eq!(schemas["Roles"]["enum"], json!(["admin", "user"]));
+    }
+
+    /// A model section of the documentation, its rows being name, type and format
+    fn model_section(name: &str, rows: &[(&str, &str, &str)]) -> String {
+        let rows: String = rows
+            .iter()
+            .map(|(name, raw_type, format)| {
+                format!(
+                    "<tr><td><p><strong>{}</strong></p></td><td><p>{}</p></td><td></td><td></td><td><p>{}</p></td></tr>",
+                    name, raw_type, format
+                )
+            })
+            .collect();
+        format!(
+            r#"<div class="sect2">
+                <h3 id="{name}">{name}</h3>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>Name</th><th>Type</th><th>Description</th><th>Required</th>
+                            <th>Format</th>
+                        </tr>
+                    </thead>
+                    <tbody>{rows}</tbody>
+                </table>
+            </div>"#,
+            name = name,
+            rows = rows
+        )
+    }
+
+    #[test]
+    fn hoists_the_enums_of_documented_models() {
+        let html = format!(
+            r#"<h2 id="models">Models</h2><div class="sectionbody">{}{}{}</div>"#,
+            model_section(
+                "PolicyRepresentation",
+                &[
+                    ("logic", "enum (POSITIVE, NEGATIVE)", ""),
+                    ("type", "enum (role, user)", ""),
+                    (
+                        "decisionStrategy",
+                        "String",
+                        "<em>Enum:</em> AFFIRMATIVE, UNANIMOUS,"
+                    ),
+                ],
+            ),
+            model_section("PolicyRepresentationType", &[("name", "String", "")]),
+            model_section("Type", &[("name", "String", "")]),
+        );
+        let document = Html::parse_document(&html);
+        let mut diagnostics = Diagnostics::default();
+        let index = Index::new(&document, Layout::current(), &mut diagnostics);
+        let api = scrape(&index, &mut diagnostics);
+
+        let schemas = serde_json::to_value(parse_schemas(&api, true)).unwrap();
+        assert_eq!(
+            schemas["PolicyRepresentation"]["properties"],
+            json!({
+                "logic": { "$ref": "#/components/schemas/Logic" },
+                "type": { "$ref": "#/components/schemas/PolicyRepresentationType2" },
+                "decisionStrategy": { "$ref": "#/components/schemas/DecisionStrategy" },
+            })
+        );
+        assert_eq!(schemas["Logic"]["enum"], json!(["POSITIVE", "NEGATIVE"]));
+        assert_eq!(
+            schemas["PolicyRepresentationType2"]["enum"],
+            json!(["role", "user"])
+        );
+        assert_eq!(
+            schemas["DecisionStrategy"]["enum"],
+            json!(["AFFIRMATIVE", "UNANIMOUS"])
+        );
+        assert_eq!(
+            schemas["PolicyRepresentationType"]["properties"]["name"],
+            json!({ "type": "string" })
+        );
+
+        let schemas = serde_json::to_value(parse_schemas(&api, false)).unwrap();
+        assert_eq!(
+            schemas["PolicyRepresentation"]["properties"],
+            json!({
+                "logic": { "type": "string", "enum": ["POSITIVE", "NEGATIVE"] },
+                "type": { "type": "string", "enum": ["role", "user"] },
+                "decisionStrategy": { "type": "string" },
+            })
+        );
+        assert!(schemas.get("Logic").is_none());
+    }
+
+    #[test]
+    fn numbers_enum_names_instead_of_replacing_models() {
+        let api = Api {
+            models: vec![
+                model("Type", &[]),
+                model("PolicyRepresentation", &[("type", "enum (role, user)")]),
+                model("PolicyRepresentationType", &[("name", "String")]),
+            ],
+            ..Default::default()
+        };
+        let schemas = serde_json::to_value(parse_schemas(&api, true)).unwrap();
+
+        assert_eq!(
+            schemas["PolicyRepresentation"]["properties"]["type"],
+            json!({ "$ref": "#/components/schemas/PolicyRepresentationType2" })
+        );
+        assert_eq!(
+            schemas["PolicyRepresentationType"]["properties"]["name"],
+            json!({ "type": "string" })
+        );
+        assert_eq!(
+            schemas["PolicyRepresentationType2"]["enum"],
+            json!(["role", "user"])
+        );
+        assert_eq!(schemas["Type"], json!({ "type": "object" }));
+    }
+
+    #[test]
+    fn turns_empty_models_with_listed_values_into_enums() {
+        let mut policy = model(
+            "PolicyRepresentation",
+            &[("logic", "Logic"), ("decisionStrategy", "String")],
+        );
+        policy.properties[0].values = vec!["POSITIVE".into(), "NEGATIVE".into()];
+        policy.properties[1].values = vec!["AFFIRMATIVE".into(), "UNANIMOUS".into()];
+        let api = Api {
+            models: vec![policy, model("Logic", &[])],
+            ..Default::default()
+        };
+
+        let schemas = serde_json::to_value(parse_schemas(&api, true)).unwrap();
+        assert_eq!(
+            schemas["PolicyRepresentation"]["properties"],
+            json!({
+                "logic": { "$ref": "#/components/schemas/Logic" },
+                "decisionStrategy": { "$ref": "#/components/schemas/DecisionStrategy" },
+            })
+        );
+        assert_eq!(
+            schemas["Logic"],
+            json!({ "type": "string", "enum": ["POSITIVE", "NEGATIVE"] })
+        );
+        assert_eq!(
+            schemas["DecisionStrategy"]["enum"],
+            json!(["AFFIRMATIVE", "UNANIMOUS"])
+        );
+
+        let inline = serde_json::to_value(parse_schemas(&api, false)).unwrap();
+        assert_eq!(inline["Logic"], json!({ "type": "object" }));
+        assert_eq!(
+            inline["PolicyRepresentation"]["properties"]["decisionStrategy"],
+            json!({ "type": "string" })
+        );
+    }
+
+    #[test]
+    fn emits_enums_from_the_documentation() {
+        let schemas = &TRANSFORMED.spec.components.as_ref().unwrap().schemas;
+        let schema = |name: &str| serde_json::to_value(&schemas[name]).unwrap();
+
+        assert_eq!(schema("Logic")["enum"], json!(["POSITIVE", "NEGATIVE"]));
+        assert_eq!(
+            schema("DecisionStrategy")["enum"],
+            json!(["AFFIRMATIVE", "UNANIMOUS", "CONSENSUS"])
+        );
+        // Every model keeps its properties, none is replaced by an enum
+        let committed: Value = serde_json::from_str(JSON).unwrap();
+        for (name, model) in committed["components"]["schemas"].as_object().unwrap() {
+            if let Some(properties) = model["properties"].as_object() {
+                assert_eq!(
+                    schema(name)["properties"]
+                        .as_object()
+                        .map(|transformed| transformed.keys().collect::<Vec<_>>()),
+                    Some(properties.keys().collect()),
+                    "{}",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn values_of_any_type_are_objects() {
+        assert_eq!(
+            serde_json::to_value(parse_type("oas_any_type_not_mapped")).unwrap(),
+            json!({ "type": "object" })
+        );
+    }
+
+    #[test]
+    fn parses_swagger2markup_types() {
+        let parsed = |raw_type: &str| serde_json::to_value(parse_type(raw_type)).unwrap();
+
+        assert_eq!(
+            parsed("< string > array"),
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+        assert_eq!(
+            parsed("< string, < string > array > map"),
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "array", "items": { "type": "string" } }
+            })
+        );
+        assert_eq!(
+            parsed("integer (int64)"),
+            json!({ "type": "integer", "format": "int64" })
+        );
+        assert_eq!(
+            parsed("< UserRepresentation > array")["items"],
+            json!({ "$ref": "#/components/schemas/UserRepresentation" })
+        );
+    }
 }
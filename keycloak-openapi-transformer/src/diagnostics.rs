@@ -0,0 +1,255 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use scraper::element_ref::ElementRef;
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+
+use crate::error::TransformError;
+use crate::SOURCE_ANCHOR_EXTENSION;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Something was left out on purpose, as OpenAPI can't express it
+    Info,
+    /// Something was defaulted or guessed, but nothing was lost
+    Warning,
+    /// Part of the documentation was left out of the definition
+    Error,
+    /// The definition breaks a rule of OpenAPI
+    Invalid,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+            Severity::Invalid => write!(f, "invalid"),
+        }
+    }
+}
+
+/// The section of the documentation a diagnostic came from
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Location {
+    /// The `id` of the section's heading, usable as a URL fragment
+    pub anchor: Option<String>,
+    /// The text of the section's heading
+    pub heading: Option<String>,
+}
+
+impl Location {
+    /// Locate a section by its first heading
+    pub fn of(section: &ElementRef<'_>) -> Self {
+        let heading = section
+            .children()
+            .filter_map(ElementRef::wrap)
+            .find(|child| {
+                matches!(
+                    child.value().name(),
+                    "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+                )
+            });
+
+        match heading {
+            Some(heading) => Location {
+                anchor: heading
+                    .value()
+                    .id()
+                    .or_else(|| section.value().id())
+                    .map(str::to_string),
+                heading: Some(heading.text().collect::<String>().trim().to_string()),
+            },
+            None => Location {
+                anchor: section.value().id().map(str::to_string),
+                heading: None,
+            },
+        }
+    }
+
+    /// Locate an operation or schema by the section it records it came from
+    pub fn source(extensions: &IndexMap<String, serde_json::Value>) -> Self {
+        Location {
+            anchor: extensions
+                .get(SOURCE_ANCHOR_EXTENSION)
+                .and_then(|anchor| anchor.as_str())
+                .map(str::to_string),
+            heading: None,
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.anchor, &self.heading) {
+            (Some(anchor), Some(heading)) => write!(f, "#{} ({})", anchor, heading),
+            (Some(anchor), None) => write!(f, "#{}", anchor),
+            (None, Some(heading)) => write!(f, "{}", heading),
+            (None, None) => write!(f, "document"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub location: Location,
+    pub error: TransformError,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}: {}", self.severity, self.location, self.error)
+    }
+}
+
+impl Serialize for Diagnostic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut diagnostic = serializer.serialize_struct("Diagnostic", 4)?;
+        diagnostic.serialize_field("code", self.error.code())?;
+        diagnostic.serialize_field("severity", &self.severity)?;
+        diagnostic.serialize_field("location", &self.location)?;
+        diagnostic.serialize_field("message", &self.error.to_string())?;
+        diagnostic.end()
+    }
+}
+
+/// Everything that went wrong while transforming a document
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub fn info(&mut self, location: Location, error: TransformError) {
+        self.0.push(Diagnostic {
+            severity: Severity::Info,
+            location,
+            error,
+        });
+    }
+
+    pub fn warn(&mut self, location: Location, error: TransformError) {
+        self.0.push(Diagnostic {
+            severity: Severity::Warning,
+            location,
+            error,
+        });
+    }
+
+    pub fn error(&mut self, location: Location, error: TransformError) {
+        self.0.push(Diagnostic {
+            severity: Severity::Error,
+            location,
+            error,
+        });
+    }
+
+    pub fn invalid(&mut self, location: Location, error: TransformError) {
+        self.0.push(Diagnostic {
+            severity: Severity::Invalid,
+            location,
+            error,
+        });
+    }
+
+    pub fn extend(&mut self, other: Diagnostics) {
+        self.0.extend(other.0);
+    }
+
+    /// Whether anything was defaulted, left out by mistake or is invalid
+    pub fn has_warnings(&self) -> bool {
+        self.0.iter().any(|d| d.severity != Severity::Info)
+    }
+
+    /// Whether the definition failed validation
+    pub fn is_invalid(&self) -> bool {
+        self.0.iter().any(|d| d.severity == Severity::Invalid)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for diagnostic in self.0.iter() {
+            writeln!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+/// The serializations diagnostics can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiagnosticsFormat {
+    /// One line per diagnostic
+    Text,
+    /// An array of objects with the diagnostic's `code`, `severity`,
+    /// `location` and `message`
+    Json,
+}
+
+impl DiagnosticsFormat {
+    pub fn render(self, diagnostics: &Diagnostics) -> serde_json::Result<String> {
+        match self {
+            Self::Text => Ok(diagnostics.to_string()),
+            Self::Json => Ok(format!("{}\n", serde_json::to_string_pretty(diagnostics)?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostics, DiagnosticsFormat, Location};
+    use crate::error::TransformError;
+
+    #[test]
+    fn renders_json() {
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.warn(
+            Location {
+                anchor: Some("_users_get".into()),
+                heading: Some("GET /admin/realms/{realm}/users".into()),
+            },
+            TransformError::DefaultedStatusCode(None),
+        );
+
+        let rendered: serde_json::Value =
+            serde_json::from_str(&DiagnosticsFormat::Json.render(&diagnostics).unwrap()).unwrap();
+        assert_eq!(
+            rendered,
+            serde_json::json!([{
+                "code": "defaulted-status-code",
+                "severity": "warning",
+                "location": {
+                    "anchor": "_users_get",
+                    "heading": "GET /admin/realms/{realm}/users"
+                },
+                "message": "Endpoint has no status code, assumed 2XX"
+            }])
+        );
+    }
+}
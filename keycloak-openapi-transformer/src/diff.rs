@@ -0,0 +1,145 @@
+use std::fmt;
+
+use serde_json::Value;
+
+/// A single difference between two JSON documents, located by JSON pointer
+#[derive(Debug, PartialEq)]
+pub enum Difference {
+    Added {
+        pointer: String,
+        actual: Value,
+    },
+    Removed {
+        pointer: String,
+        expected: Value,
+    },
+    Changed {
+        pointer: String,
+        expected: Value,
+        actual: Value,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difference::Added { pointer, actual } => write!(f, "+ {} {}", pointer, actual),
+            Difference::Removed { pointer, expected } => write!(f, "- {} {}", pointer, expected),
+            Difference::Changed {
+                pointer,
+                expected,
+                actual,
+            } => write!(f, "~ {} {} -> {}", pointer, expected, actual),
+        }
+    }
+}
+
+/// List the differences that turn `expected` into `actual`
+///
+/// Objects are compared key by key and arrays index by index, so a change
+/// deep inside the document is reported at its own location.
+pub fn diff(expected: &Value, actual: &Value) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    diff_into(&mut differences, String::new(), expected, actual);
+    differences
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn diff_into(differences: &mut Vec<Difference>, pointer: String, expected: &Value, actual: &Value) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_value) in expected.iter() {
+                let pointer = format!("{}/{}", pointer, escape(key));
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        diff_into(differences, pointer, expected_value, actual_value)
+                    }
+                    None => differences.push(Difference::Removed {
+                        pointer,
+                        expected: expected_value.clone(),
+                    }),
+                }
+            }
+            for (key, actual_value) in actual.iter() {
+                if !expected.contains_key(key) {
+                    differences.push(Difference::Added {
+                        pointer: format!("{}/{}", pointer, escape(key)),
+                        actual: actual_value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for (index, expected_value) in expected.iter().enumerate() {
+                let pointer = format!("{}/{}", pointer, index);
+                match actual.get(index) {
+                    Some(actual_value) => {
+                        diff_into(differences, pointer, expected_value, actual_value)
+                    }
+                    None => differences.push(Difference::Removed {
+                        pointer,
+                        expected: expected_value.clone(),
+                    }),
+                }
+            }
+            for (index, actual_value) in actual.iter().enumerate().skip(expected.len()) {
+                differences.push(Difference::Added {
+                    pointer: format!("{}/{}", pointer, index),
+                    actual: actual_value.clone(),
+                });
+            }
+        }
+        (expected, actual) if expected != actual => differences.push(Difference::Changed {
+            pointer,
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, Difference};
+    use serde_json::json;
+
+    #[test]
+    fn identical_documents_have_no_differences() {
+        let document = json!({"paths": {"/": {"get": {"tags": ["Realms Admin"]}}}});
+
+        assert_eq!(diff(&document, &document), vec![]);
+    }
+
+    #[test]
+    fn locates_differences_by_escaped_pointer() {
+        let expected = json!({"paths": {"/{realm}": {"get": {}, "put": {}}}, "tags": ["a"]});
+        let actual =
+            json!({"paths": {"/{realm}": {"get": {"operationId": "x"}}}, "tags": ["b", "c"]});
+
+        assert_eq!(
+            diff(&expected, &actual),
+            vec![
+                Difference::Added {
+                    pointer: "/paths/~1{realm}/get/operationId".into(),
+                    actual: json!("x"),
+                },
+                Difference::Removed {
+                    pointer: "/paths/~1{realm}/put".into(),
+                    expected: json!({}),
+                },
+                Difference::Changed {
+                    pointer: "/tags/0".into(),
+                    expected: json!("a"),
+                    actual: json!("b"),
+                },
+                Difference::Added {
+                    pointer: "/tags/1".into(),
+                    actual: json!("c"),
+                },
+            ]
+        );
+    }
+}
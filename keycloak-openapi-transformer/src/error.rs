@@ -0,0 +1,148 @@
+/// A part of the documentation that could not be transformed
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformError {
+    /// A required element is missing from the document
+    NoFindErr(String),
+    /// A resource section has no heading to name its tag
+    MissingTagTitle,
+    /// An endpoint section has neither a `pre` nor a heading giving its verb and path
+    MissingVerbPath,
+    /// An endpoint's verb and path could not be split apart
+    MalformedVerbPath(String),
+    /// An endpoint uses a verb OpenAPI path items have no slot for
+    UnexpectedHttpVerb(String),
+    /// An endpoint section has no responses table
+    MissingResponseTable,
+    /// An endpoint's responses table lacks a description or schema cell
+    MissingResponseCell(&'static str),
+    /// A parameters table row has no name
+    MissingParameterName,
+    /// A model section has no heading to name its schema
+    MissingSchemaTitle,
+    /// A model table row has no property name
+    MissingPropertyName,
+    /// A model table row has no type cell for the named property
+    MissingPropertyType(String),
+    /// An endpoint's path cannot be expressed as an OpenAPI path, such as `/{any}`
+    UnrepresentablePath(String),
+    /// An endpoint's success status code is missing or invalid, so `2XX` was assumed
+    DefaultedStatusCode(Option<String>),
+    /// Two documents define the same path or operation
+    ConflictingPath(String),
+    /// Two documents define different schemas of the same name
+    ConflictingSchema(String),
+    /// No documentation layout has the requested name
+    UnknownLayout(String),
+    /// The definition refers to a schema it doesn't define
+    UnresolvedReference(String),
+    /// More than one operation has the same id
+    DuplicateOperationId(String),
+    /// A path's template names a parameter none of its operations declare
+    UndeclaredPathParameter { path: String, parameter: String },
+    /// An existing OpenAPI definition could not be read
+    MalformedDefinition(String),
+    /// The input is of a kind that is recognised but can't be transformed, such as AsciiDoc
+    UnsupportedInput(&'static str),
+}
+
+impl TransformError {
+    /// A short, stable name for the kind of problem
+    pub fn code(&self) -> &'static str {
+        match self {
+            TransformError::NoFindErr(_) => "no-find",
+            TransformError::MissingTagTitle => "missing-tag-title",
+            TransformError::MissingVerbPath => "missing-verb-path",
+            TransformError::MalformedVerbPath(_) => "malformed-verb-path",
+            TransformError::UnexpectedHttpVerb(_) => "unexpected-http-verb",
+            TransformError::MissingResponseTable => "missing-response-table",
+            TransformError::MissingResponseCell(_) => "missing-response-cell",
+            TransformError::MissingParameterName => "missing-parameter-name",
+            TransformError::MissingSchemaTitle => "missing-schema-title",
+            TransformError::MissingPropertyName => "missing-property-name",
+            TransformError::MissingPropertyType(_) => "missing-property-type",
+            TransformError::UnrepresentablePath(_) => "unrepresentable-path",
+            TransformError::DefaultedStatusCode(_) => "defaulted-status-code",
+            TransformError::ConflictingPath(_) => "conflicting-path",
+            TransformError::ConflictingSchema(_) => "conflicting-schema",
+            TransformError::UnknownLayout(_) => "unknown-layout",
+            TransformError::UnresolvedReference(_) => "unresolved-reference",
+            TransformError::DuplicateOperationId(_) => "duplicate-operation-id",
+            TransformError::UndeclaredPathParameter { .. } => "undeclared-path-parameter",
+            TransformError::MalformedDefinition(_) => "malformed-definition",
+            TransformError::UnsupportedInput(_) => "unsupported-input",
+        }
+    }
+}
+
+impl std::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TransformError::NoFindErr(selector) => {
+                write!(f, "Could not find element by {}", selector)
+            }
+            TransformError::MissingTagTitle => write!(f, "Resource section has no title"),
+            TransformError::MissingVerbPath => {
+                write!(f, "Endpoint section has no HTTP verb and path")
+            }
+            TransformError::MalformedVerbPath(text) => {
+                write!(f, "Could not split {:?} into an HTTP verb and path", text)
+            }
+            TransformError::UnexpectedHttpVerb(verb) => {
+                write!(f, "Unexpected HTTP verb: {:?}", verb)
+            }
+            TransformError::MissingResponseTable => {
+                write!(f, "Endpoint section has no responses table")
+            }
+            TransformError::MissingResponseCell(cell) => {
+                write!(f, "Responses table has no {} cell", cell)
+            }
+            TransformError::MissingParameterName => write!(f, "Parameter has no name"),
+            TransformError::MissingSchemaTitle => write!(f, "Model section has no title"),
+            TransformError::MissingPropertyName => write!(f, "Model property has no name"),
+            TransformError::MissingPropertyType(property) => {
+                write!(f, "Model property {:?} has no type", property)
+            }
+            TransformError::UnrepresentablePath(verb_path) => {
+                write!(f, "{} cannot be represented as an OpenAPI path", verb_path)
+            }
+            TransformError::DefaultedStatusCode(Some(code)) => {
+                write!(f, "Invalid status code {:?}, assumed 2XX", code)
+            }
+            TransformError::DefaultedStatusCode(None) => {
+                write!(f, "Endpoint has no status code, assumed 2XX")
+            }
+            TransformError::ConflictingPath(path) => {
+                write!(
+                    f,
+                    "{} is defined by more than one document, kept the first",
+                    path
+                )
+            }
+            TransformError::UnknownLayout(name) => write!(f, "Unknown layout {:?}", name),
+            TransformError::ConflictingSchema(name) => write!(
+                f,
+                "Schema {:?} is defined differently by more than one document, kept the first",
+                name
+            ),
+            TransformError::UnresolvedReference(name) => {
+                write!(f, "Schema {:?} is referenced but not defined", name)
+            }
+            TransformError::DuplicateOperationId(id) => {
+                write!(f, "Operation id {:?} is used more than once", id)
+            }
+            TransformError::UndeclaredPathParameter { path, parameter } => {
+                write!(f, "Parameter {:?} of {} is not declared", parameter, path)
+            }
+            TransformError::MalformedDefinition(error) => {
+                write!(f, "Could not read the OpenAPI definition: {}", error)
+            }
+            TransformError::UnsupportedInput(kind) => write!(
+                f,
+                "{} input is not supported, transform the HTML documentation generated from it instead",
+                kind
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
@@ -0,0 +1,219 @@
+use std::fs;
+use std::io::Read;
+
+use crate::cache::{self, Checksum};
+use crate::cli::FetchArgs;
+use crate::manifest::Manifest;
+
+pub fn run(args: &FetchArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let mut manifest = Manifest::load(&args.manifest)?;
+    if let Some(docs_url) = &args.docs_url {
+        manifest.docs_url = docs_url.clone();
+    }
+    let cache = manifest.cache();
+    let mut checksums = manifest.load_checksums()?;
+
+    for requested in args.versions.iter() {
+        match manifest.versions.iter().find(|v| &v.version == requested) {
+            None => return Err(format!("{}: not in {}", requested, args.manifest.display()).into()),
+            Some(version) if version.html.is_none() => {
+                return Err(format!("{}: has no documentation to fetch", requested).into())
+            }
+            Some(_) => {}
+        }
+    }
+
+    let license = manifest.license_path();
+    if args.refresh || !license.exists() {
+        let contents = download(&manifest.license_url)
+            .map_err(|e| format!("{}: {}", manifest.license_url, e))?;
+        fs::write(&license, contents)?;
+        eprintln!("{}: fetched", license.display());
+    }
+
+    // Versions without documentation are converted from their committed JSON instead
+    for version in manifest
+        .versions
+        .iter()
+        .filter(|v| v.html.is_some())
+        .filter(|v| args.versions.is_empty() || args.versions.contains(&v.version))
+    {
+        let previous = checksums.get(&version.version).cloned();
+        let cached = previous
+            .as_ref()
+            .filter(|previous| !args.refresh && cache.contains(&previous.sha256));
+        let html = &manifest.html_paths(version)[0];
+        let contents = match cached {
+            Some(_) if html.exists() => {
+                eprintln!("{}: cached", version.version);
+                continue;
+            }
+            Some(previous) => {
+                eprintln!("{}: cached", version.version);
+                cache.get(&previous.sha256)?
+            }
+            None => {
+                let url = manifest.docs_url(version);
+                let contents = download(&url).map_err(|e| format!("{}: {}", url, e))?;
+                let sha256 = verify(&contents, previous.as_ref(), args.refresh)
+                    .map_err(|e| format!("{}: {} {}", version.version, url, e))?;
+                cache.insert(&contents)?;
+
+                checksums.insert(version.version.clone(), Checksum { url, sha256 });
+                checksums.save(&manifest.checksums_path())?;
+                eprintln!("{}: fetched", version.version);
+                contents
+            }
+        };
+
+        // The version's `html` is what regenerating and the tests read it from
+        if args.refresh || !html.exists() {
+            if let Some(dir) = html.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(html, contents)?;
+            eprintln!("{}: written", html.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Check downloaded contents against the recorded checksum before they are cached,
+/// returning their checksum
+fn verify(contents: &[u8], previous: Option<&Checksum>, refresh: bool) -> Result<String, String> {
+    let sha256 = cache::sha256(contents);
+    match previous {
+        Some(previous) if previous.sha256 != sha256 && !refresh => Err(format!(
+            "has checksum {}, expected {}. Pass --refresh to accept the change",
+            sha256, previous.sha256
+        )),
+        _ => Ok(sha256),
+    }
+}
+
+fn download(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut contents = Vec::new();
+    ureq::get(url)
+        .call()?
+        .into_reader()
+        .read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{download, run, verify};
+    use crate::cache::{sha256, Checksum, Checksums};
+    use crate::cli::FetchArgs;
+    use std::fs;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve one response from a local stand-in for keycloak.org
+    fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        format!("http://{}/docs-api", address)
+    }
+
+    #[test]
+    fn downloads_from_the_configured_url() {
+        let docs_url = serve("<h1>Keycloak Admin REST API</h1>");
+
+        assert_eq!(
+            download(&format!("{}/23.0.7/rest-api/index.html", docs_url)).unwrap(),
+            b"<h1>Keycloak Admin REST API</h1>"
+        );
+    }
+
+    #[test]
+    fn refuses_changed_contents_unless_refreshing() {
+        let previous = Checksum {
+            url: "https://www.keycloak.org/docs-api/23.0.7/rest-api/index.html".into(),
+            sha256: sha256(b"<h1>Keycloak</h1>"),
+        };
+
+        assert_eq!(
+            verify(b"<h1>Keycloak</h1>", Some(&previous), false),
+            Ok(previous.sha256.clone())
+        );
+        assert!(verify(b"<h1>Changed</h1>", Some(&previous), false).is_err());
+        assert_eq!(
+            verify(b"<h1>Changed</h1>", Some(&previous), true),
+            Ok(sha256(b"<h1>Changed</h1>"))
+        );
+        assert_eq!(
+            verify(b"<h1>Changed</h1>", None, false),
+            Ok(sha256(b"<h1>Changed</h1>"))
+        );
+    }
+
+    #[test]
+    fn fetches_the_manifest_versions_into_their_html() {
+        let dir = std::env::temp_dir().join(format!(
+            "keycloak-openapi-transformer-fetch-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("keycloak")).unwrap();
+        // Already fetched, so the one response served is the documentation's
+        fs::write(dir.join("keycloak/LICENSE.txt"), "Apache License").unwrap();
+        let docs_url = serve("<h1>Keycloak Admin REST API</h1>");
+        let manifest = serde_json::json!({
+            "output_dir": "keycloak",
+            "docs_url": docs_url,
+            "versions": [
+                { "version": "12.0" },
+                { "version": "23.0.7", "html": "keycloak/23.0.7.html" }
+            ]
+        });
+        fs::write(dir.join("versions.json"), manifest.to_string()).unwrap();
+        let args = FetchArgs {
+            manifest: dir.join("versions.json"),
+            versions: Vec::new(),
+            docs_url: None,
+            refresh: false,
+        };
+
+        run(&args).unwrap();
+
+        let html = dir.join("keycloak/23.0.7.html");
+        assert_eq!(
+            fs::read_to_string(&html).unwrap(),
+            "<h1>Keycloak Admin REST API</h1>"
+        );
+        let checksums = Checksums::load(&dir.join("checksums.json")).unwrap();
+        let checksum = checksums.get("23.0.7").unwrap();
+        assert_eq!(
+            checksum.url,
+            format!("{}/23.0.7/rest-api/index.html", docs_url)
+        );
+        assert_eq!(checksum.sha256, sha256(b"<h1>Keycloak Admin REST API</h1>"));
+        assert!(checksums.get("12.0").is_none());
+
+        // Written again from the cache, as nothing is served any more
+        fs::remove_file(&html).unwrap();
+        run(&args).unwrap();
+        assert_eq!(
+            fs::read_to_string(&html).unwrap(),
+            "<h1>Keycloak Admin REST API</h1>"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! The 22.0.0 documentation, parsed and transformed once for every test to share
+
+use indexmap::IndexMap;
+use openapiv3::{ReferenceOr, Schema};
+use scraper::Html;
+
+use crate::components::schemas::parse_schemas;
+use crate::diagnostics::Diagnostics;
+use crate::index::Index;
+use crate::info;
+use crate::ir;
+use crate::layout::Layout;
+use crate::paths::paths;
+use crate::scrape::scrape;
+use crate::{transform, Options, Transformed};
+
+pub const HTML: &str = include_str!("../../keycloak/22.0.0.html");
+pub const JSON: &str = include_str!("../../keycloak/22.0.0.json");
+
+/// What each stage produces from the fixture
+pub struct Stages {
+    /// The info block, without a Keycloak release to use as its version
+    pub info: openapiv3::Info,
+    pub api: ir::Api,
+    pub paths: openapiv3::Paths,
+    pub schemas: IndexMap<String, ReferenceOr<Schema>>,
+}
+
+lazy_static! {
+    pub static ref STAGES: Stages = {
+        let document = Html::parse_document(HTML);
+        let mut diagnostics = Diagnostics::default();
+        let index = Index::new(&document, Layout::current(), &mut diagnostics);
+        let api = scrape(&index, &mut diagnostics);
+        let (paths, _) = paths(&api);
+        let schemas = parse_schemas(&api, true);
+        Stages {
+            info: info::parse(&document, None).unwrap(),
+            api,
+            paths,
+            schemas,
+        }
+    };
+    /// The whole definition, with default options
+    pub static ref TRANSFORMED: Transformed = transform(HTML, &Options::default()).unwrap();
+    /// The whole definition, with the switches `versions.json` regenerates the
+    /// committed definition with
+    pub static ref COMMITTED: Transformed = transform(
+        HTML,
+        &Options {
+            keycloak_version: Some("22.0.0".into()),
+            source_anchors: false,
+            schema_descriptions: false,
+            required_properties: false,
+            enum_schemas: false,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+}
@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The serializations a definition can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    #[value(alias = "yml")]
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Guess the format from a file's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "yml" | "yaml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// The extension the committed definitions use for this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yml",
+        }
+    }
+
+    pub fn render<T: Serialize>(self, value: &T) -> serde_json::Result<String> {
+        match self {
+            Self::Json => Ok(format!("{}\n", serde_json::to_string_pretty(value)?)),
+            Self::Yaml => crate::yaml::to_string(value),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use openapiv3::{
+    OpenAPI, Parameter, ParameterSchemaOrContent, PathItem, ReferenceOr, Schema, SchemaKind,
+    StringFormat, Type, VariantOrUnknownOrEmpty,
+};
+
+use heck::ToSnakeCase;
+
+use crate::paths;
+
+/// What a rule recognises a property or parameter by, such as a [`FormatRule`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Match {
+    /// The whole name, such as `email`
+    Name(String),
+    /// The last words of a camelCase name, such as `Url` in `redirectUrl`,
+    /// ignoring case, so `redirectURL` and `url` match too but `hurl` doesn't
+    Suffix(String),
+    /// Words in the description, such as `ISO-8601`, ignoring case
+    Hint(String),
+}
+
+impl Match {
+    pub fn matches(&self, name: &str, description: Option<&str>) -> bool {
+        match self {
+            Match::Name(expected) => name == expected,
+            Match::Suffix(suffix) => {
+                let name = name.to_snake_case();
+                let suffix = suffix.to_snake_case();
+                name == suffix || name.ends_with(&format!("_{}", suffix))
+            }
+            Match::Hint(hint) => matches!(
+                description,
+                Some(description) if description.to_lowercase().contains(&hint.to_lowercase())
+            ),
+        }
+    }
+}
+
+/// Gives string properties and parameters a format, such as `uuid`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatRule {
+    pub matches: Match,
+    pub format: String,
+}
+
+impl FormatRule {
+    pub fn new(matches: Match, format: &str) -> Self {
+        FormatRule {
+            matches,
+            format: format.to_string(),
+        }
+    }
+
+    fn matches(&self, name: &str, description: Option<&str>) -> bool {
+        self.matches.matches(name, description)
+    }
+}
+
+/// The rules Keycloak's naming conventions call for, in the order they are tried
+pub fn default_rules() -> Vec<FormatRule> {
+    vec![
+        FormatRule::new(Match::Hint("date-time".into()), "date-time"),
+        FormatRule::new(Match::Hint("ISO-8601".into()), "date-time"),
+        // Only ids the documentation describes as UUIDs, as some are aliases or client ids
+        FormatRule::new(Match::Hint("UUID".into()), "uuid"),
+        // Keycloak accepts relative URLs, such as a client's `/realms/master/app`
+        FormatRule::new(Match::Suffix("Url".into()), "uri-reference"),
+        FormatRule::new(Match::Suffix("Urls".into()), "uri-reference"),
+        FormatRule::new(Match::Suffix("Uri".into()), "uri-reference"),
+        FormatRule::new(Match::Suffix("Uris".into()), "uri-reference"),
+        FormatRule::new(Match::Suffix("Email".into()), "email"),
+    ]
+}
+
+/// Give the definition's string properties and parameters the format of the first rule they match
+///
+/// Parameters of every kind, response headers and the properties of inline
+/// request and response bodies are formatted as well. A request body has no
+/// name, so only its description is matched. Strings that already have a
+/// format or list their values are left alone. Arrays of strings, such as
+/// `redirectUris`, have their items formatted.
+pub fn infer(spec: &mut OpenAPI, rules: &[FormatRule]) {
+    if rules.is_empty() {
+        return;
+    }
+
+    if let Some(components) = &mut spec.components {
+        for schema in components.schemas.values_mut() {
+            if let ReferenceOr::Item(schema) = schema {
+                infer_properties(schema, rules);
+            }
+        }
+    }
+
+    for path_item in spec.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path_item) = path_item {
+            infer_path_item(path_item, rules);
+        }
+    }
+}
+
+fn infer_properties(schema: &mut Schema, rules: &[FormatRule]) {
+    if let SchemaKind::Type(Type::Object(object)) = &mut schema.schema_kind {
+        for (name, property) in object.properties.iter_mut() {
+            if let ReferenceOr::Item(property) = property {
+                let description = property.schema_data.description.clone();
+                apply(property, name, description.as_deref(), rules);
+                infer_properties(property, rules);
+            }
+        }
+    }
+}
+
+fn infer_path_item(path_item: &mut PathItem, rules: &[FormatRule]) {
+    for parameter in path_item.parameters.iter_mut() {
+        infer_parameter(parameter, rules);
+    }
+    for operation in paths::operations_mut(path_item) {
+        for parameter in operation.parameters.iter_mut() {
+            infer_parameter(parameter, rules);
+        }
+        if let Some(ReferenceOr::Item(body)) = &mut operation.request_body {
+            for media_type in body.content.values_mut() {
+                if let Some(ReferenceOr::Item(schema)) = &mut media_type.schema {
+                    apply(schema, "", body.description.as_deref(), rules);
+                    infer_properties(schema, rules);
+                }
+            }
+        }
+        let responses = operation
+            .responses
+            .default
+            .iter_mut()
+            .chain(operation.responses.responses.values_mut());
+        for response in responses {
+            if let ReferenceOr::Item(response) = response {
+                for (name, header) in response.headers.iter_mut() {
+                    if let ReferenceOr::Item(header) = header {
+                        if let ParameterSchemaOrContent::Schema(ReferenceOr::Item(schema)) =
+                            &mut header.format
+                        {
+                            apply(schema, name, header.description.as_deref(), rules);
+                        }
+                    }
+                }
+                for media_type in response.content.values_mut() {
+                    if let Some(ReferenceOr::Item(schema)) = &mut media_type.schema {
+                        infer_properties(schema, rules);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn infer_parameter(parameter: &mut ReferenceOr<Parameter>, rules: &[FormatRule]) {
+    let parameter_data = match parameter {
+        ReferenceOr::Item(Parameter::Path { parameter_data, .. })
+        | ReferenceOr::Item(Parameter::Query { parameter_data, .. })
+        | ReferenceOr::Item(Parameter::Header { parameter_data, .. })
+        | ReferenceOr::Item(Parameter::Cookie { parameter_data, .. }) => parameter_data,
+        ReferenceOr::Reference { .. } => return,
+    };
+    if let ParameterSchemaOrContent::Schema(ReferenceOr::Item(schema)) = &mut parameter_data.format
+    {
+        apply(
+            schema,
+            &parameter_data.name,
+            parameter_data.description.as_deref(),
+            rules,
+        );
+    }
+}
+
+fn apply(schema: &mut Schema, name: &str, description: Option<&str>, rules: &[FormatRule]) {
+    match &mut schema.schema_kind {
+        SchemaKind::Type(Type::String(string)) => {
+            if string.format != VariantOrUnknownOrEmpty::Empty || !string.enumeration.is_empty() {
+                return;
+            }
+            if let Some(rule) = rules.iter().find(|rule| rule.matches(name, description)) {
+                string.format = match rule.format.as_str() {
+                    "date-time" => VariantOrUnknownOrEmpty::Item(StringFormat::DateTime),
+                    "date" => VariantOrUnknownOrEmpty::Item(StringFormat::Date),
+                    "password" => VariantOrUnknownOrEmpty::Item(StringFormat::Password),
+                    "byte" => VariantOrUnknownOrEmpty::Item(StringFormat::Byte),
+                    "binary" => VariantOrUnknownOrEmpty::Item(StringFormat::Binary),
+                    format => VariantOrUnknownOrEmpty::Unknown(format.to_string()),
+                };
+            }
+        }
+        SchemaKind::Type(Type::Array(array)) => {
+            if let Some(ReferenceOr::Item(items)) = &mut array.items {
+                apply(items, name, description, rules);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_rules, infer, FormatRule, Match};
+    use crate::fixture::HTML;
+    use crate::{transform, Options};
+    use openapiv3::OpenAPI;
+    use serde_json::json;
+
+    #[test]
+    fn formats_what_the_rules_match() {
+        let mut spec: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.2",
+            "info": { "title": "Keycloak Admin REST API", "version": "1" },
+            "paths": {
+                "/{realm}/users/{id}": {
+                    "parameters": [
+                        { "in": "path", "name": "id", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "get": {
+                        "parameters": [
+                            {
+                                "in": "query",
+                                "name": "since",
+                                "description": "An ISO-8601 date",
+                                "schema": { "type": "string" }
+                            },
+                            { "in": "header", "name": "redirectUri", "schema": { "type": "string" } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "success",
+                                "headers": {
+                                    "locationUrl": { "schema": { "type": "string" } }
+                                },
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": { "baseUrl": { "type": "string" } }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "put": {
+                        "requestBody": {
+                            "description": "An ISO-8601 date",
+                            "content": {
+                                "application/json": { "schema": { "type": "string" } }
+                            }
+                        },
+                        "responses": {}
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "ClientRepresentation": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "string" },
+                            "clientId": { "type": "string" },
+                            "internalId": { "type": "string", "description": "The client's UUID" },
+                            "redirectURL": { "type": "string" },
+                            "curl": { "type": "string" },
+                            "xxxCurl": { "type": "string" },
+                            "hurl": { "type": "string" },
+                            "baseUrl": { "type": "string" },
+                            "redirectUris": { "type": "array", "items": { "type": "string" } },
+                            "email": { "type": "string", "format": "idn-email" },
+                            "protocol": { "type": "string", "enum": ["openid-connect"] },
+                            "maxUrls": { "type": "integer", "format": "int32" },
+                            "secret": { "type": "string" }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let mut rules = default_rules();
+        rules.push(FormatRule::new(Match::Name("secret".into()), "password"));
+        infer(&mut spec, &rules);
+
+        let spec = serde_json::to_value(spec).unwrap();
+        let properties = &spec["components"]["schemas"]["ClientRepresentation"]["properties"];
+        assert_eq!(properties["baseUrl"]["format"], "uri-reference");
+        assert_eq!(
+            properties["redirectUris"]["items"]["format"],
+            "uri-reference"
+        );
+        assert_eq!(properties["secret"]["format"], "password");
+        assert_eq!(properties["internalId"]["format"], "uuid");
+        assert_eq!(properties["redirectURL"]["format"], "uri-reference");
+        // Only a whole last word matches a suffix
+        assert_eq!(properties["curl"].get("format"), None);
+        assert_eq!(properties["xxxCurl"].get("format"), None);
+        assert_eq!(properties["hurl"].get("format"), None);
+        // Ids not described as UUIDs are left alone, existing formats are kept
+        // and only strings are formatted
+        assert_eq!(properties["id"].get("format"), None);
+        assert_eq!(properties["clientId"].get("format"), None);
+        assert_eq!(properties["email"]["format"], "idn-email");
+        assert_eq!(properties["protocol"].get("format"), None);
+        assert_eq!(properties["maxUrls"]["format"], "int32");
+
+        let path = &spec["paths"]["/{realm}/users/{id}"];
+        assert_eq!(path["parameters"][0]["schema"].get("format"), None);
+        let get = &path["get"];
+        assert_eq!(get["parameters"][0]["schema"]["format"], "date-time");
+        assert_eq!(get["parameters"][1]["schema"]["format"], "uri-reference");
+        let response = &get["responses"]["200"];
+        assert_eq!(
+            response["headers"]["locationUrl"]["schema"]["format"],
+            "uri-reference"
+        );
+        assert_eq!(
+            response["content"]["application/json"]["schema"]["properties"]["baseUrl"]["format"],
+            "uri-reference"
+        );
+        assert_eq!(
+            path["put"]["requestBody"]["content"]["application/json"]["schema"]["format"],
+            "date-time"
+        );
+    }
+
+    #[test]
+    fn formats_the_documentation() {
+        let options = Options {
+            format_rules: default_rules(),
+            ..Default::default()
+        };
+        let spec = serde_json::to_value(transform(HTML, &options).unwrap().spec).unwrap();
+        let schemas = &spec["components"]["schemas"];
+
+        let client = &schemas["ClientRepresentation"]["properties"];
+        assert_eq!(client["baseUrl"]["format"], "uri-reference");
+        assert_eq!(client["redirectUris"]["items"]["format"], "uri-reference");
+        assert_eq!(client["id"].get("format"), None);
+        assert_eq!(client["clientId"].get("format"), None);
+        let user = &schemas["UserRepresentation"]["properties"];
+        assert_eq!(user["email"]["format"], "email");
+        assert_eq!(user["createdTimestamp"]["format"], "int64");
+    }
+}
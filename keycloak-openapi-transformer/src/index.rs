@@ -0,0 +1,187 @@
+use scraper::element_ref::ElementRef;
+use scraper::Html;
+
+use crate::diagnostics::{Diagnostics, Location};
+use crate::error::TransformError;
+use crate::layout::Layout;
+use crate::paths::verb_path::VerbPath;
+
+/// One documented endpoint
+pub struct Endpoint<'a> {
+    pub verb_path: VerbPath,
+    /// The OpenAPI path, with repeated `{id}` parameters numbered
+    pub path: String,
+    /// Position among all the document's endpoints
+    pub position: usize,
+    pub section: ElementRef<'a>,
+}
+
+/// A resource's endpoints, which share a tag
+pub struct Resource<'a> {
+    pub tag: String,
+    pub endpoints: Vec<Endpoint<'a>>,
+}
+
+/// One documented model
+pub struct Model<'a> {
+    pub name: String,
+    pub section: ElementRef<'a>,
+}
+
+/// The sections of a document, found in a single pass
+///
+/// Sections that can't be indexed, such as endpoints whose verb and path
+/// can't be read, are reported and left out so later stages never see them.
+pub struct Index<'a> {
+    pub layout: &'static Layout,
+    pub resources: Vec<Resource<'a>>,
+    pub models: Vec<Model<'a>>,
+}
+
+impl<'a> Index<'a> {
+    pub fn new(document: &'a Html, layout: &'static Layout, diagnostics: &mut Diagnostics) -> Self {
+        let mut resources = Vec::new();
+        let mut position = 0;
+
+        for tag_section in document.select(&layout.tag_section) {
+            let tag = match tag_section.select(&layout.tag_title).next() {
+                Some(title) => title.text().collect::<String>(),
+                None => {
+                    diagnostics.error(Location::of(&tag_section), TransformError::MissingTagTitle);
+                    continue;
+                }
+            };
+
+            let mut endpoints = Vec::new();
+            for section in tag_section.select(&layout.path_section) {
+                let verb_path = match verb_path_split(&section, layout) {
+                    Ok(verb_path) => verb_path,
+                    Err(error) => {
+                        diagnostics.error(Location::of(&section), error);
+                        continue;
+                    }
+                };
+                let path = verb_path.path();
+
+                if verb_path.unrepresentable() {
+                    diagnostics.info(
+                        Location::of(&section),
+                        TransformError::UnrepresentablePath(format!("{} {}", verb_path.verb, path)),
+                    );
+                    continue;
+                }
+
+                endpoints.push(Endpoint {
+                    verb_path,
+                    path,
+                    position,
+                    section,
+                });
+                position += 1;
+            }
+            resources.push(Resource { tag, endpoints });
+        }
+
+        let models = document
+            .select(&layout.schemas)
+            .filter_map(
+                |section| match section.select(&layout.schema_title).next() {
+                    Some(title) => Some(Model {
+                        name: title.text().collect(),
+                        section,
+                    }),
+                    None => {
+                        diagnostics
+                            .error(Location::of(&section), TransformError::MissingSchemaTitle);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Index {
+            layout,
+            resources,
+            models,
+        }
+    }
+
+    /// Every endpoint, in document order
+    pub fn endpoints(&self) -> impl Iterator<Item = &Endpoint<'a>> {
+        self.resources
+            .iter()
+            .flat_map(|resource| resource.endpoints.iter())
+    }
+}
+
+fn verb_path_split(section: &ElementRef<'_>, layout: &Layout) -> Result<VerbPath, TransformError> {
+    section
+        .select(&layout.verb_path)
+        .next()
+        .or_else(|| section.select(&layout.summary).next())
+        .ok_or(TransformError::MissingVerbPath)?
+        .text()
+        .collect::<String>()
+        .parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Index;
+    use crate::diagnostics::Diagnostics;
+    use crate::layout::Layout;
+    use scraper::Html;
+
+    const HTML: &str = r#"
+        <h2 id="_resources">Resources</h2>
+        <div class="sectionbody">
+            <div class="sect2">
+                <h3>Clients</h3>
+                <div class="sect3"><h4>GET /admin/realms/{realm}/clients</h4></div>
+                <div class="sect3"><h4>TRACE /admin/realms/{realm}/clients</h4></div>
+            </div>
+            <div class="sect2">
+                <h3>Users</h3>
+                <div class="sect3"><h4>GET /admin/realms/{realm}/users/{id}/groups/{id}</h4></div>
+            </div>
+        </div>
+        <h2 id="models">Models</h2>
+        <div class="sectionbody">
+            <div class="sect2"><h3>UserRepresentation</h3></div>
+        </div>
+    "#;
+
+    #[test]
+    fn indexes_sections_in_document_order() {
+        let document = Html::parse_document(HTML);
+        let mut diagnostics = Diagnostics::default();
+        let index = Index::new(&document, Layout::current(), &mut diagnostics);
+
+        let endpoints: Vec<_> = index
+            .resources
+            .iter()
+            .flat_map(|resource| {
+                resource.endpoints.iter().map(move |endpoint| {
+                    (
+                        resource.tag.as_str(),
+                        endpoint.verb_path.verb.as_str(),
+                        endpoint.path.as_str(),
+                        endpoint.position,
+                    )
+                })
+            })
+            .collect();
+        assert_eq!(
+            endpoints,
+            vec![
+                ("Clients", "GET", "/{realm}/clients", 0),
+                ("Users", "GET", "/{realm}/users/{id1}/groups/{id2}", 1),
+            ]
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            index.models.iter().map(|m| &m.name).collect::<Vec<_>>(),
+            vec!["UserRepresentation"]
+        );
+    }
+}
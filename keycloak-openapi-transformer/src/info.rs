@@ -1,31 +1,24 @@
 use openapiv3::Info;
+use regex::Regex;
 use scraper::Selector;
 
+use crate::error::TransformError;
+
+/// Extension holding the version the documentation gives its own API
+pub const API_VERSION_EXTENSION: &str = "x-api-version";
+
 lazy_static! {
     static ref TITLE_SELECTOR: Selector = Selector::parse("h1").unwrap();
     static ref DESCRIPTION_SELECTOR: Selector =
         Selector::parse(".sectionbody > .paragraph p").unwrap();
     static ref VERSION_SELECTOR: Selector =
         Selector::parse("#_version_information + .paragraph").unwrap();
+    static ref LINK_SELECTOR: Selector = Selector::parse("a[href]").unwrap();
+    static ref DOCS_URL_VERSION_REGEX: Regex =
+        Regex::new(r"/docs-api/([0-9]+(?:\.[0-9]+)+)/").unwrap();
+    static ref TITLE_VERSION_REGEX: Regex = Regex::new(r"Keycloak ([0-9]+(?:\.[0-9]+)+)").unwrap();
 }
 
-#[derive(Debug, PartialEq)]
-pub enum TransformError {
-    NoFindErr(String),
-}
-
-impl std::fmt::Display for TransformError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            TransformError::NoFindErr(selector) => {
-                write!(f, "Could not find element by {}", selector)
-            }
-        }
-    }
-}
-
-impl std::error::Error for TransformError {}
-
 fn extract_string(
     document: &scraper::html::Html,
     selector: &Selector,
@@ -40,35 +33,72 @@ fn extract_string(
         .to_string())
 }
 
-pub fn parse(document: &scraper::html::Html) -> Result<Info, TransformError> {
+/// Find the Keycloak release from links to the published docs or the title
+///
+/// The documentation's own version information is the API version, which has
+/// been `1.0` for every release.
+pub fn detect_keycloak_version(document: &scraper::html::Html) -> Option<String> {
+    document
+        .select(&LINK_SELECTOR)
+        .filter_map(|a| a.value().attr("href"))
+        .find_map(|href| capture_version(&DOCS_URL_VERSION_REGEX, href))
+        .or_else(|| {
+            document.select(&TITLE_SELECTOR).find_map(|h1| {
+                capture_version(&TITLE_VERSION_REGEX, &h1.text().collect::<String>())
+            })
+        })
+}
+
+fn capture_version(regex: &Regex, text: &str) -> Option<String> {
+    regex.captures(text).map(|captures| captures[1].to_string())
+}
+
+/// Parse the info block, using the Keycloak release as the version if known
+pub fn parse(
+    document: &scraper::html::Html,
+    keycloak_version: Option<&str>,
+) -> Result<Info, TransformError> {
+    let api_version: String = extract_string(document, &VERSION_SELECTOR)?
+        .split("Version: ")
+        .collect();
+
+    let mut extensions = indexmap::IndexMap::new();
+    extensions.insert(
+        API_VERSION_EXTENSION.to_string(),
+        serde_json::Value::String(api_version.clone()),
+    );
+
     Ok(Info {
         title: extract_string(document, &TITLE_SELECTOR)?,
         description: Some(extract_string(document, &DESCRIPTION_SELECTOR)?),
-        version: extract_string(document, &VERSION_SELECTOR)?
-            .split("Version: ")
-            .collect(),
+        version: keycloak_version.map(str::to_string).unwrap_or(api_version),
         contact: Some(openapiv3::Contact {
             name: None,
             url: Some("https://www.keycloak.org/documentation".into()),
             email: None,
             extensions: Default::default(),
         }),
+        extensions,
         ..Default::default()
     })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse;
+    use super::API_VERSION_EXTENSION;
+    use crate::fixture::{COMMITTED, JSON, STAGES};
     use openapiv3::OpenAPI;
-    use scraper::Html;
 
     #[test]
     fn parses_as_expected() {
-        const HTML: &str = include_str!("../../keycloak/22.0.0.html");
-        const JSON: &str = include_str!("../../keycloak/22.0.0.json");
         let openapi: OpenAPI = serde_json::from_str(JSON).expect("Could not deserialize example");
 
-        assert_eq!(Ok(openapi.info), parse(&Html::parse_document(HTML)));
+        assert_eq!(COMMITTED.spec.info, openapi.info);
+    }
+
+    #[test]
+    fn falls_back_to_the_api_version() {
+        assert_eq!(STAGES.info.version, "1.0");
+        assert_eq!(STAGES.info.extensions[API_VERSION_EXTENSION], "1.0");
     }
 }
@@ -0,0 +1,108 @@
+/// The kinds of document the transformer reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// Published HTML documentation
+    Html,
+    /// The AsciiDoc source the HTML documentation is generated from, which
+    /// isn't supported, so it is rejected rather than misread as HTML
+    AsciiDoc,
+    /// An existing OpenAPI definition, which is normalised rather than transformed
+    OpenApiJson,
+    OpenApiYaml,
+}
+
+impl InputKind {
+    /// Tell the kind of a document from its contents
+    ///
+    /// Anything not recognised is assumed to be HTML, which is parsed leniently.
+    pub fn detect(input: &str) -> Self {
+        let input = input.trim_start_matches('\u{feff}').trim_start();
+        if input.starts_with('{') {
+            return Self::OpenApiJson;
+        }
+        if input.starts_with('<') {
+            return Self::Html;
+        }
+        if input.lines().any(|line| line.starts_with("openapi:")) {
+            return Self::OpenApiYaml;
+        }
+        if input.starts_with("= ")
+            || input
+                .lines()
+                .any(|line| line.starts_with("== ") || line.trim_end() == "|===")
+        {
+            return Self::AsciiDoc;
+        }
+        Self::Html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputKind;
+    use crate::error::TransformError;
+    use crate::{transform, Options, OutputFormat};
+
+    #[test]
+    fn detects_the_kind_of_input() {
+        assert_eq!(
+            InputKind::detect("<!DOCTYPE html><html></html>"),
+            InputKind::Html
+        );
+        assert_eq!(
+            InputKind::detect("= Keycloak Admin REST API\n\n== Resources\n"),
+            InputKind::AsciiDoc
+        );
+        assert_eq!(
+            InputKind::detect("\u{feff}{\"openapi\": \"3.0.2\"}"),
+            InputKind::OpenApiJson
+        );
+        assert_eq!(
+            InputKind::detect("---\nopenapi: 3.0.2\ninfo: {}\n"),
+            InputKind::OpenApiYaml
+        );
+    }
+
+    #[test]
+    fn normalises_existing_definitions() {
+        // The oldest, a hand patched one and the newest of the committed definitions
+        const DEFINITIONS: [(&str, &str); 3] = [
+            (
+                include_str!("../../keycloak/5.0.json"),
+                include_str!("../../keycloak/5.0.yml"),
+            ),
+            (
+                include_str!("../../keycloak/12.0-patched.json"),
+                include_str!("../../keycloak/12.0-patched.yml"),
+            ),
+            (
+                include_str!("../../keycloak/23.0.7.json"),
+                include_str!("../../keycloak/23.0.7.yml"),
+            ),
+        ];
+        let render = |input: &str| {
+            OutputFormat::Json
+                .render(&transform(input, &Options::default()).unwrap().spec)
+                .unwrap()
+        };
+
+        for (json, yaml) in DEFINITIONS.iter() {
+            assert_eq!(render(yaml), render(json));
+            assert_eq!(render(&render(json)), render(json));
+        }
+    }
+
+    #[test]
+    fn rejects_asciidoc() {
+        let diagnostics = transform(
+            "= Keycloak Admin REST API\n\n== Resources\n\n|===\n|Name |Type\n|===\n",
+            &Options::default(),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            diagnostics.iter().map(|d| &d.error).collect::<Vec<_>>(),
+            vec![&TransformError::UnsupportedInput("AsciiDoc")]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! The API a document describes, independent of how it is written out
+//!
+//! [`crate::scrape::scrape`] reads it from a document's sections and
+//! [`crate::paths::paths`] and [`crate::components::schemas::parse_schemas`]
+//! emit it as OpenAPI 3.0. Text is kept as the documentation wrote it, so a
+//! type stays `List[UserRepresentation]` until an emitter decides what that means.
+//! Model and property descriptions are the exception, converted from the page's
+//! markup to CommonMark.
+
+/// Everything a document describes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Api {
+    pub resources: Vec<Resource>,
+    pub models: Vec<Model>,
+}
+
+impl Api {
+    /// Every endpoint, in document order
+    pub fn endpoints(&self) -> impl Iterator<Item = &Endpoint> {
+        self.resources
+            .iter()
+            .flat_map(|resource| resource.endpoints.iter())
+    }
+}
+
+/// A resource's endpoints, which share a tag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resource {
+    pub tag: String,
+    pub endpoints: Vec<Endpoint>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Endpoint {
+    /// Such as `GET`
+    pub verb: String,
+    /// The OpenAPI path, with repeated `{id}` parameters numbered
+    pub path: String,
+    /// Position among all the document's endpoints
+    pub position: usize,
+    /// The `id` of the endpoint's section
+    pub anchor: Option<String>,
+    pub description: Option<String>,
+    /// In the order they appear in the path
+    pub path_params: Vec<Param>,
+    pub query_params: Vec<Param>,
+    pub body: Option<Body>,
+    /// The status code of a successful response, when the documentation gives a valid one
+    pub status_code: Option<u16>,
+    /// The successful response, when the documentation has a responses table
+    pub response: Option<Response>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Body {
+    /// The type as written, such as `UserRepresentation`
+    pub raw_type: String,
+    /// Properties the body must have beyond those its type requires, such as
+    /// those needed to create the model
+    pub required: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Response {
+    pub description: String,
+    /// Such as `application/json`, when the endpoint says what it produces
+    pub media_type: Option<String>,
+    /// The type as written, `<<>>` when there is no content
+    pub raw_type: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Model {
+    pub name: String,
+    /// The `id` of the model's section
+    pub anchor: Option<String>,
+    pub description: Option<String>,
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Property {
+    pub name: String,
+    /// The type as written, such as `Map[String]`
+    pub raw_type: String,
+    pub description: Option<String>,
+    pub required: bool,
+    /// The values the documentation lists for an enumerated property, such as
+    /// one whose type is an enum model left without properties
+    pub values: Vec<String>,
+}
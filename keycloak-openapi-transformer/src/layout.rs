@@ -0,0 +1,195 @@
+use scraper::{Html, Selector};
+
+/// Where each part of the API is found in one style of documentation
+///
+/// Keycloak's REST API documentation has been generated by different tools
+/// over the years, each laying the same information out differently.
+#[derive(Debug)]
+pub struct Layout {
+    /// The name the layout is chosen by
+    pub name: &'static str,
+    /// Matches something only documentation of this layout has
+    pub marker: Selector,
+    /// Sections holding a resource's endpoints, one per tag
+    pub tag_section: Selector,
+    /// The tag's name within its section
+    pub tag_title: Selector,
+    /// Sections holding one endpoint each
+    pub path_section: Selector,
+    /// The endpoint's heading
+    pub summary: Selector,
+    /// Preformatted `VERB /path` of the endpoint, preferred over its heading
+    pub verb_path: Selector,
+    /// The paragraph describing the endpoint
+    pub description: Selector,
+    /// The first cell of the responses table
+    pub status_code: Selector,
+    /// The rows of the responses table
+    pub responses: Selector,
+    /// The media type the endpoint produces
+    pub produces: Selector,
+    /// Within a response row
+    pub response_description: Selector,
+    /// Within a response row
+    pub response_schema: Selector,
+    pub path_params_table: Selector,
+    pub query_params_table: Selector,
+    pub body_param_table: Selector,
+    /// Column of a shared parameters table telling which kind of parameter a
+    /// row is, such as `Path`, `Query` or `Body`
+    pub parameter_kind_column: Option<&'static str>,
+    /// Column of the body parameter table holding its type
+    pub body_type_column: &'static str,
+    /// Sections holding one model each
+    pub schemas: Selector,
+    /// The model's name within its section
+    pub schema_title: Selector,
+    /// Paragraphs describing the model
+    pub schema_description: Selector,
+    /// The rows of the model's properties table
+    pub property_rows: Selector,
+    /// Within a property row
+    pub property_name: Selector,
+    /// Within a property row
+    pub property_type: Selector,
+    /// Column of the properties table holding each property's description
+    pub property_description_column: &'static str,
+    /// Column of the properties table marking required properties
+    pub property_required_column: &'static str,
+    /// Column of the properties table holding each property's format, which
+    /// lists the values of an enumerated property after `Enum:`
+    pub property_format_column: &'static str,
+    /// Within a property row, `required` or `optional` beneath the name
+    pub property_optionality: Selector,
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).unwrap()
+}
+
+lazy_static! {
+    /// Documentation generated by asciidoctor from the OpenAPI definition, as
+    /// published for the releases in `versions.json` with documentation, 22.0.0 onwards
+    static ref ASCIIDOCTOR: Layout = Layout {
+        name: "asciidoctor",
+        marker: selector("#models"),
+        tag_section: selector("#_resources + .sectionbody > .sect2"),
+        tag_title: selector("h3"),
+        path_section: selector(".sect3"),
+        summary: selector("h4:first-child"),
+        verb_path: selector("pre"),
+        description: selector("h4:first-child + div.paragraph > p"),
+        status_code: selector(".sect4 > .stretch .valign-top:nth-child(1) .tableblock"),
+        responses: selector("h5[id^=_responses] + table > tbody > tr"),
+        produces: selector("h5[id^=_content_type] + div code"),
+        response_description: selector("td:first-child + td"),
+        response_schema: selector("td:first-child + td + td"),
+        path_params_table: selector("h6[id^=_path_parameters] + table"),
+        query_params_table: selector("h6[id^=_query_parameters] + table"),
+        body_param_table: selector("h6[id^=_body_parameter] + table"),
+        parameter_kind_column: None,
+        body_type_column: "Description",
+        schemas: selector("#models + .sectionbody > .sect2"),
+        schema_title: selector("h3"),
+        schema_description: selector("h3 ~ div.paragraph"),
+        property_rows: selector("table > tbody > tr"),
+        property_name: selector("td:first-child strong"),
+        property_type: selector("td:first-child + td"),
+        property_description_column: "Description",
+        property_required_column: "Required",
+        property_format_column: "Format",
+        property_optionality: selector("td:first-child em"),
+    };
+
+    /// Documentation generated by swagger2markup, as published for Keycloak 5.0 to 12.0
+    ///
+    /// Each endpoint has a single parameters table with a `Type` column, and
+    /// the models are listed under definitions in a `Name` and `Schema` table.
+    static ref SWAGGER2MARKUP: Layout = Layout {
+        name: "swagger2markup",
+        marker: selector("#_definitions"),
+        tag_section: selector("#_resources + .sectionbody > .sect2"),
+        tag_title: selector("h3"),
+        path_section: selector(".sect3"),
+        summary: selector("h4:first-child"),
+        verb_path: selector("pre"),
+        description: selector("h4:first-child + div.paragraph > p"),
+        status_code: selector("h5[id^=_responses] + table > tbody > tr > td:first-child"),
+        responses: selector("h5[id^=_responses] + table > tbody > tr"),
+        produces: selector("h5[id^=_produces] + div code"),
+        response_description: selector("td:first-child + td"),
+        response_schema: selector("td:first-child + td + td"),
+        path_params_table: selector("h5[id^=_parameters] + table"),
+        query_params_table: selector("h5[id^=_parameters] + table"),
+        body_param_table: selector("h5[id^=_parameters] + table"),
+        parameter_kind_column: Some("Type"),
+        body_type_column: "Schema",
+        schemas: selector("#_definitions + .sectionbody > .sect2"),
+        schema_title: selector("h3"),
+        schema_description: selector("h3 ~ div.paragraph"),
+        property_rows: selector("table > tbody > tr"),
+        property_name: selector("td:first-child strong"),
+        property_type: selector("td:last-child"),
+        property_description_column: "Description",
+        property_required_column: "Required",
+        property_format_column: "Format",
+        property_optionality: selector("td:first-child em"),
+    };
+
+    static ref LAYOUTS: [&'static Layout; 2] = [&ASCIIDOCTOR, &SWAGGER2MARKUP];
+}
+
+impl Layout {
+    /// Every known layout, newest first
+    pub fn all() -> impl Iterator<Item = &'static Layout> {
+        LAYOUTS.iter().copied()
+    }
+
+    pub fn named(name: &str) -> Option<&'static Layout> {
+        Self::all().find(|layout| layout.name == name)
+    }
+
+    /// The layout the document matches, assuming the newest if it matches none
+    pub fn detect(document: &Html) -> &'static Layout {
+        Self::all()
+            .find(|layout| document.select(&layout.marker).next().is_some())
+            .unwrap_or_else(Self::current)
+    }
+
+    /// The layout of recent releases' documentation
+    pub fn current() -> &'static Layout {
+        &ASCIIDOCTOR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Layout;
+    use scraper::Html;
+
+    #[test]
+    fn detects_the_layout() {
+        let current =
+            Html::parse_document(r#"<h2 id="models">Models</h2><div class="sectionbody"></div>"#);
+        let legacy = Html::parse_document(
+            r#"<h2 id="_definitions">Definitions</h2><div class="sectionbody"></div>"#,
+        );
+
+        assert_eq!(Layout::detect(&current).name, "asciidoctor");
+        assert_eq!(Layout::detect(&legacy).name, "swagger2markup");
+        assert_eq!(
+            Layout::detect(&Html::parse_document("")).name,
+            "asciidoctor"
+        );
+    }
+
+    #[test]
+    fn layouts_are_found_by_name() {
+        assert_eq!(Layout::named("asciidoctor").unwrap().name, "asciidoctor");
+        assert_eq!(
+            Layout::named("swagger2markup").unwrap().name,
+            "swagger2markup"
+        );
+        assert!(Layout::named("javadoc").is_none());
+    }
+}
@@ -0,0 +1,384 @@
+//! Transform Keycloak's HTML REST API documentation into OpenAPI definitions
+//!
+//! [`transform`] produces a whole definition along with [`Diagnostics`]
+//! describing any part of the documentation it had to leave out or guess. The parsers it is built from,
+//! such as [`paths::paths`], [`components::schemas::parse_schemas`] and
+//! [`components::schemas::parse_type`], can be used on their own. The first
+//! two emit an [`ir::Api`], which [`scrape::scrape`] reads from an
+//! [`index::Index`] of the document's sections, built once.
+
+use indexmap::IndexMap;
+use openapiv3::{OpenAPI, ReferenceOr, SecurityRequirement, SecurityScheme};
+use scraper::Html;
+
+#[macro_use]
+extern crate lazy_static;
+
+pub mod archive;
+pub mod components;
+pub mod diagnostics;
+pub mod diff;
+pub mod error;
+#[cfg(test)]
+mod fixture;
+pub mod format;
+pub mod formats;
+pub mod index;
+pub mod info;
+pub mod input;
+pub mod ir;
+pub mod layout;
+pub mod markdown;
+pub mod merge;
+pub mod ordering;
+pub mod paths;
+pub mod report;
+pub mod required;
+pub mod scrape;
+pub mod servers;
+pub mod table;
+pub mod timestamps;
+pub mod validate;
+pub mod yaml;
+
+pub use diagnostics::{Diagnostics, DiagnosticsFormat};
+pub use error::TransformError;
+pub use format::OutputFormat;
+pub use report::Report;
+
+const ACCESS_TOKEN: &str = "access_token";
+
+/// Extension naming the documentation section an operation or schema came from
+pub const SOURCE_ANCHOR_EXTENSION: &str = "x-source-anchor";
+
+/// Settings that shape the definition beyond what the documentation says
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The Keycloak release the documentation describes, such as `23.0.7`
+    pub keycloak_version: Option<String>,
+    pub servers: servers::ServerOptions,
+    /// URL of the published documentation, such as
+    /// `https://www.keycloak.org/docs-api/23.0.7/rest-api/index.html`.
+    /// When given, operations and schemas link to their section of it in `externalDocs`
+    pub docs_url: Option<String>,
+    pub ordering: ordering::Ordering,
+    /// Name of the [`layout::Layout`] the documentation uses. Detected when absent
+    pub layout: Option<String>,
+    /// Corrections to which model properties are required, such as [`required::RequiredOverrides::curated`]
+    pub required_overrides: Option<required::RequiredOverrides>,
+    /// Rules giving string properties and parameters a format, such as
+    /// [`formats::default_rules`]. None are applied when empty
+    pub format_rules: Vec<formats::FormatRule>,
+    /// Rules recognising epoch-millisecond timestamps and dates, such as
+    /// [`timestamps::default_rules`]. None are marked when empty
+    pub timestamp_rules: Vec<formats::Match>,
+    /// Use the Keycloak release as `info.version`, keeping the documentation's
+    /// own API version in `x-api-version`. Otherwise `info.version` is the API version
+    pub release_version: bool,
+    /// Record the section each operation and schema came from in [`SOURCE_ANCHOR_EXTENSION`]
+    pub source_anchors: bool,
+    /// Describe schemas and their properties as the documentation does
+    pub schema_descriptions: bool,
+    /// List the properties the documentation marks required
+    pub required_properties: bool,
+    /// Give enumerated properties a named enum schema, rather than listing their values inline
+    pub enum_schemas: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            keycloak_version: None,
+            servers: Default::default(),
+            docs_url: None,
+            ordering: Default::default(),
+            layout: None,
+            required_overrides: None,
+            format_rules: Vec::new(),
+            timestamp_rules: Vec::new(),
+            release_version: true,
+            source_anchors: true,
+            schema_descriptions: true,
+            required_properties: true,
+            enum_schemas: true,
+        }
+    }
+}
+
+/// A definition and the problems met while producing it
+#[derive(Debug, Clone)]
+pub struct Transformed {
+    pub spec: OpenAPI,
+    pub diagnostics: Diagnostics,
+}
+
+/// Transform a Keycloak REST API documentation page
+///
+/// The page may be HTML, or an existing OpenAPI definition in JSON or YAML,
+/// which is normalised instead; see
+/// [`input::InputKind::detect`].
+///
+/// Fails only when no definition can be produced at all. Sections that could
+/// not be transformed are left out and reported in the diagnostics instead,
+/// as is anything about the definition that fails [`validate::validate`].
+pub fn transform(input: &str, options: &Options) -> Result<Transformed, Diagnostics> {
+    let transformed = transform_input(input, options)?;
+    Ok(finish(transformed, options))
+}
+
+/// Transform several documentation pages into one definition
+///
+/// The info and servers come from the first page. Paths, tags and components
+/// of later pages are merged in, reporting any that conflict with earlier ones.
+pub fn transform_all<S: AsRef<str>>(
+    inputs: &[S],
+    options: &Options,
+) -> Result<Transformed, Diagnostics> {
+    let mut diagnostics = Diagnostics::default();
+    let mut merged: Option<OpenAPI> = None;
+    let mut failed = false;
+
+    for input in inputs {
+        match transform_input(input.as_ref(), options) {
+            Ok(transformed) => {
+                diagnostics.extend(transformed.diagnostics);
+                match &mut merged {
+                    None => merged = Some(transformed.spec),
+                    Some(spec) => merge::merge(spec, transformed.spec, &mut diagnostics),
+                }
+            }
+            Err(errors) => {
+                diagnostics.extend(errors);
+                failed = true;
+            }
+        }
+    }
+
+    match merged {
+        Some(spec) if !failed => Ok(finish(Transformed { spec, diagnostics }, options)),
+        _ => Err(diagnostics),
+    }
+}
+
+/// Transform an already parsed Keycloak REST API documentation page
+pub fn transform_document(document: &Html, options: &Options) -> Result<Transformed, Diagnostics> {
+    let transformed = transform_page(document, options)?;
+    Ok(finish(transformed, options))
+}
+
+/// Order and validate a whole definition, once any pages have been merged into it
+fn finish(mut transformed: Transformed, options: &Options) -> Transformed {
+    if options.ordering == ordering::Ordering::Canonical {
+        ordering::canonicalize(&mut transformed.spec);
+    }
+    validate::validate(&transformed.spec, &mut transformed.diagnostics);
+    transformed
+}
+
+/// Transform a page of any kind without ordering or validating it, as it may be one of several
+fn transform_input(input: &str, options: &Options) -> Result<Transformed, Diagnostics> {
+    match input::InputKind::detect(input) {
+        input::InputKind::Html => transform_page(&Html::parse_document(input), options),
+        input::InputKind::AsciiDoc => {
+            let mut diagnostics = Diagnostics::default();
+            diagnostics.error(
+                Default::default(),
+                TransformError::UnsupportedInput("AsciiDoc"),
+            );
+            Err(diagnostics)
+        }
+        kind => normalize(input, kind, options),
+    }
+}
+
+/// Read an existing definition, linking it as a transformed one would be
+fn normalize(
+    input: &str,
+    kind: input::InputKind,
+    options: &Options,
+) -> Result<Transformed, Diagnostics> {
+    let mut diagnostics = Diagnostics::default();
+    let parsed = match kind {
+        input::InputKind::OpenApiYaml => yaml::from_str(input)
+            .and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string())),
+        _ => serde_json::from_str(input).map_err(|e| e.to_string()),
+    };
+    let mut spec: OpenAPI = match parsed {
+        Ok(spec) => spec,
+        Err(error) => {
+            diagnostics.error(
+                Default::default(),
+                TransformError::MalformedDefinition(error),
+            );
+            return Err(diagnostics);
+        }
+    };
+
+    annotate(&mut spec, options);
+
+    Ok(Transformed { spec, diagnostics })
+}
+
+/// Transform a page without ordering or validating it
+fn transform_page(document: &Html, options: &Options) -> Result<Transformed, Diagnostics> {
+    let mut diagnostics = Diagnostics::default();
+
+    let mut security_schemes = IndexMap::new();
+    security_schemes.insert(
+        ACCESS_TOKEN.to_string(),
+        ReferenceOr::Item(SecurityScheme::HTTP {
+            scheme: "bearer".to_string(),
+            bearer_format: None,
+            description: None,
+        }),
+    );
+
+    let mut security_requirement: SecurityRequirement = IndexMap::new();
+    security_requirement.insert(ACCESS_TOKEN.to_string(), Vec::new());
+
+    let keycloak_version = options
+        .keycloak_version
+        .clone()
+        .or_else(|| info::detect_keycloak_version(document));
+
+    let layout = match &options.layout {
+        None => layout::Layout::detect(document),
+        Some(name) => match layout::Layout::named(name) {
+            Some(layout) => layout,
+            None => {
+                diagnostics.error(
+                    Default::default(),
+                    TransformError::UnknownLayout(name.clone()),
+                );
+                return Err(diagnostics);
+            }
+        },
+    };
+
+    let info = match info::parse(document, keycloak_version.as_deref()) {
+        Ok(mut info) if !options.release_version => {
+            if let Some(serde_json::Value::String(api_version)) =
+                info.extensions.shift_remove(info::API_VERSION_EXTENSION)
+            {
+                info.version = api_version;
+            }
+            info
+        }
+        Ok(info) => info,
+        Err(error) => {
+            diagnostics.error(Default::default(), error);
+            return Err(diagnostics);
+        }
+    };
+
+    let index = index::Index::new(document, layout, &mut diagnostics);
+    let mut api = scrape::scrape(&index, &mut diagnostics);
+    for model in api.models.iter_mut() {
+        if !options.schema_descriptions {
+            model.description = None;
+        }
+        for property in model.properties.iter_mut() {
+            if !options.schema_descriptions {
+                property.description = None;
+            }
+            if !options.required_properties {
+                property.required = false;
+            }
+        }
+    }
+    if let Some(overrides) = &options.required_overrides {
+        overrides.apply(&mut api);
+    }
+    let (paths, tags) = paths::paths(&api);
+
+    let tags = tags
+        .into_iter()
+        .map(|tag| openapiv3::Tag {
+            name: tag,
+            ..Default::default()
+        })
+        .collect();
+
+    let schemas = components::schemas::parse_schemas(&api, options.enum_schemas);
+
+    let mut spec = OpenAPI {
+        openapi: "3.0.2".to_string(),
+        info,
+        components: Some(openapiv3::Components {
+            schemas,
+            security_schemes,
+            ..Default::default()
+        }),
+        paths,
+        security: Some(vec![security_requirement]),
+        tags,
+        servers: servers::servers(keycloak_version.as_deref(), &options.servers),
+        ..Default::default()
+    };
+
+    annotate(&mut spec, options);
+
+    Ok(Transformed { spec, diagnostics })
+}
+
+/// Add what the options ask for beyond what the documentation says
+fn annotate(spec: &mut OpenAPI, options: &Options) {
+    formats::infer(spec, &options.format_rules);
+    timestamps::mark(spec, &options.timestamp_rules);
+    if let Some(docs_url) = &options.docs_url {
+        link_docs(spec, docs_url);
+    }
+    if !options.source_anchors {
+        strip_source_anchors(spec);
+    }
+}
+
+/// Point operations and schemas at the section of the documentation they came from
+fn link_docs(spec: &mut OpenAPI, docs_url: &str) {
+    let external_docs = |extensions: &IndexMap<String, serde_json::Value>| {
+        extensions
+            .get(SOURCE_ANCHOR_EXTENSION)
+            .and_then(|anchor| anchor.as_str())
+            .map(|anchor| openapiv3::ExternalDocumentation {
+                description: None,
+                url: format!("{}#{}", docs_url, anchor),
+                extensions: Default::default(),
+            })
+    };
+
+    for path_item in spec.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path_item) = path_item {
+            for operation in paths::operations_mut(path_item) {
+                operation.external_docs = external_docs(&operation.extensions);
+            }
+        }
+    }
+    if let Some(components) = &mut spec.components {
+        for schema in components.schemas.values_mut() {
+            if let ReferenceOr::Item(schema) = schema {
+                schema.schema_data.external_docs = external_docs(&schema.schema_data.extensions);
+            }
+        }
+    }
+}
+
+/// Remove the sections operations and schemas record they came from
+fn strip_source_anchors(spec: &mut OpenAPI) {
+    for path_item in spec.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path_item) = path_item {
+            for operation in paths::operations_mut(path_item) {
+                operation.extensions.shift_remove(SOURCE_ANCHOR_EXTENSION);
+            }
+        }
+    }
+    if let Some(components) = &mut spec.components {
+        for schema in components.schemas.values_mut() {
+            if let ReferenceOr::Item(schema) = schema {
+                schema
+                    .schema_data
+                    .extensions
+                    .shift_remove(SOURCE_ANCHOR_EXTENSION);
+            }
+        }
+    }
+}
@@ -1,68 +1,91 @@
-use openapiv3::{OpenAPI, ReferenceOr, SecurityRequirement, SecurityScheme};
-use scraper::Html;
-use serde_json::to_string_pretty;
-#[macro_use]
-extern crate lazy_static;
-use indexmap::IndexMap;
-use std::io::{self, Read};
+use std::process::ExitCode;
 
-mod components;
-mod info;
-mod paths;
-mod table;
+use clap::Parser;
+use keycloak_openapi_transformer::Diagnostics;
 
-const ACCESS_TOKEN: &str = "access_token";
+mod cache;
+mod cli;
+mod fetch;
+mod manifest;
+mod regenerate;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut html = String::new();
-    io::stdin().read_to_string(&mut html)?;
-    let document = Html::parse_document(&html);
+/// The definition was written, but parts of the documentation were left out or defaulted.
+/// Failures, including documentation no definition could be produced from,
+/// exit with 1 and usage errors with 2
+const EXIT_DIAGNOSTICS: u8 = 3;
+/// The definition was written, but fails validation
+const EXIT_INVALID: u8 = 4;
 
-    let mut security_schemes = IndexMap::new();
-    security_schemes.insert(
-        ACCESS_TOKEN.to_string(),
-        ReferenceOr::Item(SecurityScheme::HTTP {
-            scheme: "bearer".to_string(),
-            bearer_format: None,
-            description: None,
-        }),
-    );
+fn main() -> ExitCode {
+    let result = match cli::Cli::parse().into_command() {
+        cli::Command::Transform(args) => transform(&args),
+        cli::Command::Regenerate(args) => regenerate::run(&args),
+        cli::Command::Fetch(args) => fetch::run(&args).map(|()| ExitCode::SUCCESS),
+        cli::Command::Report(args) => report(&args).map(|()| ExitCode::SUCCESS),
+        cli::Command::Convert(args) => convert(&args).map(|()| ExitCode::SUCCESS),
+    };
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    let mut security_requirement: SecurityRequirement = IndexMap::new();
-    security_requirement.insert(ACCESS_TOKEN.to_string(), Vec::new());
+fn transform(args: &cli::TransformArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let htmls = cli::read_inputs(&args.inputs)?;
+    let transformed =
+        match keycloak_openapi_transformer::transform_all(&htmls, &args.options.options()?) {
+            Ok(transformed) => transformed,
+            Err(diagnostics) => {
+                args.diagnostics.write(&diagnostics)?;
+                return Err("could not produce a definition".into());
+            }
+        };
+    args.diagnostics.write(&transformed.diagnostics)?;
 
-    let (paths, tags) = paths::paths(&document);
+    cli::write_output(
+        args.output.as_deref(),
+        &args.format().render(&transformed.spec)?,
+    )?;
+
+    Ok(exit_code(&transformed.diagnostics, true))
+}
 
-    let mut tags: Vec<String> = tags.into_iter().collect();
+/// How a command that wrote definitions exits, given what was reported while
+/// transforming them and whether warnings fail it
+fn exit_code(diagnostics: &Diagnostics, deny_warnings: bool) -> ExitCode {
+    if diagnostics.is_invalid() {
+        ExitCode::from(EXIT_INVALID)
+    } else if deny_warnings && diagnostics.has_warnings() {
+        ExitCode::from(EXIT_DIAGNOSTICS)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
 
-    tags.sort();
+fn report(args: &cli::ReportArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let htmls = cli::read_inputs(&args.inputs)?;
+    let transformed = keycloak_openapi_transformer::transform_all(&htmls, &args.options.options()?)
+        .map_err(|diagnostics| {
+            eprint!("{}", diagnostics);
+            "could not produce a definition"
+        })?;
+    let report =
+        keycloak_openapi_transformer::Report::new(&transformed.spec, &transformed.diagnostics);
 
-    let tags = tags
-        .into_iter()
-        .map(|tag| openapiv3::Tag {
-            name: tag,
-            ..Default::default()
-        })
-        .collect();
+    cli::write_output(args.output.as_deref(), &args.format.render(&report)?)?;
+    Ok(())
+}
 
-    let spec = OpenAPI {
-        openapi: "3.0.2".to_string(),
-        info: info::parse(&document)?,
-        components: Some(openapiv3::Components {
-            schemas: components::schemas::parse_schemas(&document),
-            security_schemes,
-            ..Default::default()
-        }),
-        paths,
-        security: Some(vec![security_requirement]),
-        tags,
-        servers: vec![openapiv3::Server {
-            url: "https://keycloak.example.com/admin/realms".into(),
-            ..Default::default()
-        }],
-        ..Default::default()
+fn convert(args: &cli::ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let input = cli::read_input(args.input.as_deref())?;
+    // Read as plain values rather than a definition so nothing is reordered or dropped
+    let definition: serde_json::Value = match serde_json::from_str(&input) {
+        Ok(definition) => definition,
+        Err(_) => keycloak_openapi_transformer::yaml::from_str(&input)?,
     };
-
-    println!("{}", to_string_pretty(&spec)?);
+    cli::write_output(args.output.as_deref(), &args.format().render(&definition)?)?;
     Ok(())
 }
@@ -0,0 +1,335 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use keycloak_openapi_transformer::archive;
+use keycloak_openapi_transformer::formats;
+use keycloak_openapi_transformer::ordering::Ordering;
+use keycloak_openapi_transformer::required::RequiredOverrides;
+use keycloak_openapi_transformer::servers::ServerOptions;
+use keycloak_openapi_transformer::timestamps;
+use keycloak_openapi_transformer::{Options, OutputFormat};
+
+use crate::cache::{Cache, Checksums};
+
+const DEFAULT_CACHE_DIR: &str = ".cache/docs";
+const DEFAULT_CHECKSUMS: &str = "checksums.json";
+const DEFAULT_DOCS_URL: &str = "https://www.keycloak.org/docs-api";
+const DEFAULT_LICENSE_URL: &str =
+    "https://raw.githubusercontent.com/keycloak/keycloak/master/LICENSE.txt";
+
+/// The list of Keycloak versions that definitions are generated for
+///
+/// Relative paths are resolved against the directory containing the manifest.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    /// Directory the JSON and YAML definitions are written to
+    pub output_dir: PathBuf,
+    pub versions: Vec<Version>,
+    /// Directory fetched documentation is cached in
+    #[serde(default = "default_cache_dir")]
+    pub cache_dir: PathBuf,
+    /// File recording the URL and checksum of each version's fetched documentation
+    #[serde(default = "default_checksums")]
+    pub checksums: PathBuf,
+    /// Where documentation is published. A version's is at `<docs_url>/<version>/rest-api/index.html`
+    #[serde(default = "default_docs_url")]
+    pub docs_url: String,
+    /// Where Keycloak's license is published. It is fetched into the output directory
+    /// as `LICENSE.txt`, as it covers the definitions
+    #[serde(default = "default_license_url")]
+    pub license_url: String,
+    /// Switches for every version, which a version's own switches override
+    #[serde(default)]
+    pub switches: Switches,
+    #[serde(skip)]
+    base_dir: PathBuf,
+}
+
+fn default_cache_dir() -> PathBuf {
+    DEFAULT_CACHE_DIR.into()
+}
+
+fn default_checksums() -> PathBuf {
+    DEFAULT_CHECKSUMS.into()
+}
+
+fn default_docs_url() -> String {
+    DEFAULT_DOCS_URL.into()
+}
+
+fn default_license_url() -> String {
+    DEFAULT_LICENSE_URL.into()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Version {
+    /// Keycloak release, such as `23.0.7`. Also used as the output file stem
+    pub version: String,
+    /// Local copy of the version's REST API documentation. May be gzipped or
+    /// a zip or tar archive of the docs site.
+    /// Versions without documentation are no longer transformed: their YAML is
+    /// converted from their committed JSON, which may have been patched by hand
+    #[serde(default)]
+    pub html: Option<PathBuf>,
+    /// Further documentation pages merged into the definition, such as a
+    /// page describing custom REST resources
+    #[serde(default)]
+    pub extra_html: Vec<PathBuf>,
+    /// Default of the servers' `contextPath` when it differs from the release's usual path
+    #[serde(default)]
+    pub context_path: Option<String>,
+    /// Layout of the documentation, when detection picks the wrong one
+    #[serde(default)]
+    pub layout: Option<String>,
+    #[serde(flatten)]
+    pub switches: Switches,
+}
+
+/// Parts of a definition that can be turned on or off, each left as the
+/// transformer has it by default when absent
+///
+/// Definitions committed before a part was added can leave it out, so they
+/// stay exactly as they are until they are next regenerated on purpose.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Switches {
+    pub ordering: Option<Ordering>,
+    /// Literal server URLs in place of the templated server
+    pub server_urls: Option<Vec<String>>,
+    /// See [`Options::release_version`]
+    pub release_version: Option<bool>,
+    /// See [`Options::source_anchors`]
+    pub source_anchors: Option<bool>,
+    /// See [`Options::schema_descriptions`]
+    pub schema_descriptions: Option<bool>,
+    /// See [`Options::required_properties`]
+    pub required_properties: Option<bool>,
+    /// See [`Options::enum_schemas`]
+    pub enum_schemas: Option<bool>,
+    /// Correct required properties with the curated list, as `--curated-required` does
+    pub curated_required: Option<bool>,
+    /// Give strings formats from their names, as `--infer-formats` does
+    pub infer_formats: Option<bool>,
+    /// Mark timestamps, as `--mark-timestamps` does
+    pub mark_timestamps: Option<bool>,
+}
+
+impl Switches {
+    /// These switches, falling back to `defaults` for any that are absent
+    fn or(&self, defaults: &Switches) -> Switches {
+        Switches {
+            ordering: self.ordering.or(defaults.ordering),
+            server_urls: self
+                .server_urls
+                .clone()
+                .or_else(|| defaults.server_urls.clone()),
+            release_version: self.release_version.or(defaults.release_version),
+            source_anchors: self.source_anchors.or(defaults.source_anchors),
+            schema_descriptions: self.schema_descriptions.or(defaults.schema_descriptions),
+            required_properties: self.required_properties.or(defaults.required_properties),
+            enum_schemas: self.enum_schemas.or(defaults.enum_schemas),
+            curated_required: self.curated_required.or(defaults.curated_required),
+            infer_formats: self.infer_formats.or(defaults.infer_formats),
+            mark_timestamps: self.mark_timestamps.or(defaults.mark_timestamps),
+        }
+    }
+
+    fn apply(&self, options: &mut Options) {
+        if let Some(ordering) = self.ordering {
+            options.ordering = ordering;
+        }
+        if let Some(urls) = &self.server_urls {
+            options.servers.urls = urls.clone();
+        }
+        let flags = [
+            (self.release_version, &mut options.release_version),
+            (self.source_anchors, &mut options.source_anchors),
+            (self.schema_descriptions, &mut options.schema_descriptions),
+            (self.required_properties, &mut options.required_properties),
+            (self.enum_schemas, &mut options.enum_schemas),
+        ];
+        for (switch, option) in flags {
+            if let Some(switch) = switch {
+                *option = switch;
+            }
+        }
+        if let Some(curated) = self.curated_required {
+            options.required_overrides = curated.then(RequiredOverrides::curated);
+        }
+        if let Some(infer) = self.infer_formats {
+            options.format_rules = if infer {
+                formats::default_rules()
+            } else {
+                Vec::new()
+            };
+        }
+        if let Some(mark) = self.mark_timestamps {
+            options.timestamp_rules = if mark {
+                timestamps::default_rules()
+            } else {
+                Vec::new()
+            };
+        }
+    }
+}
+
+impl Version {
+    /// The formats written for the version, leaving out the committed JSON a
+    /// version without documentation is converted from
+    pub fn output_formats(&self) -> &'static [OutputFormat] {
+        match self.html {
+            Some(_) => &[OutputFormat::Json, OutputFormat::Yaml],
+            None => &[OutputFormat::Yaml],
+        }
+    }
+}
+
+impl Manifest {
+    /// How to transform a version, with its switches and then the manifest's applied
+    pub fn options(&self, version: &Version) -> Options {
+        let mut options = Options {
+            keycloak_version: Some(version.version.clone()),
+            servers: ServerOptions {
+                urls: Vec::new(),
+                context_path: version.context_path.clone(),
+            },
+            layout: version.layout.clone(),
+            ..Default::default()
+        };
+        version.switches.or(&self.switches).apply(&mut options);
+        options
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut manifest: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        manifest.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(manifest)
+    }
+
+    pub fn cache(&self) -> Cache {
+        Cache::new(self.base_dir.join(&self.cache_dir))
+    }
+
+    pub fn checksums_path(&self) -> PathBuf {
+        self.base_dir.join(&self.checksums)
+    }
+
+    pub fn load_checksums(&self) -> Result<Checksums, Box<dyn std::error::Error>> {
+        Checksums::load(&self.checksums_path())
+    }
+
+    /// Where the checksum of what a version was last regenerated from is recorded
+    pub fn stamp_path(&self, version: &Version) -> PathBuf {
+        self.base_dir
+            .join(&self.cache_dir)
+            .join("stamps")
+            .join(&version.version)
+    }
+
+    pub fn license_path(&self) -> PathBuf {
+        self.base_dir.join(&self.output_dir).join("LICENSE.txt")
+    }
+
+    /// Where a version's documentation is published
+    pub fn docs_url(&self, version: &Version) -> String {
+        format!(
+            "{}/{}/rest-api/index.html",
+            self.docs_url.trim_end_matches('/'),
+            version.version
+        )
+    }
+
+    /// The files a version's documentation is read from
+    ///
+    /// The fetched copy in the cache stands in for the version's `html` when
+    /// there is no local copy of it.
+    pub fn documentation_paths(&self, version: &Version, checksums: &Checksums) -> Vec<PathBuf> {
+        let mut paths = self.html_paths(version);
+        if version.html.is_some() && !paths[0].exists() {
+            if let Some(checksum) = checksums.get(&version.version) {
+                paths[0] = self.cache().path(&checksum.sha256);
+            }
+        }
+        paths
+    }
+
+    /// Read a version's documentation pages, verifying any cached copy against its checksum
+    pub fn read_documentation(
+        &self,
+        version: &Version,
+        checksums: &Checksums,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let cache = self.cache();
+        self.documentation_paths(version, checksums)
+            .iter()
+            .map(|path| {
+                let cached = checksums
+                    .get(&version.version)
+                    .filter(|checksum| cache.path(&checksum.sha256) == *path);
+                let contents = match cached {
+                    Some(checksum) => cache.get(&checksum.sha256).and_then(archive::decode),
+                    None => archive::read(path),
+                };
+                contents.map_err(|e| format!("{}: {}", path.display(), e).into())
+            })
+            .collect()
+    }
+
+    /// The version's documentation followed by any extra pages
+    pub fn html_paths(&self, version: &Version) -> Vec<PathBuf> {
+        version
+            .html
+            .iter()
+            .chain(version.extra_html.iter())
+            .map(|html| self.base_dir.join(html))
+            .collect()
+    }
+
+    pub fn output_path(&self, version: &Version, format: OutputFormat) -> PathBuf {
+        self.base_dir.join(&self.output_dir).join(format!(
+            "{}.{}",
+            version.version,
+            format.extension()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Manifest;
+    use keycloak_openapi_transformer::OutputFormat;
+
+    const MANIFEST: &str = include_str!("../../versions.json");
+
+    #[test]
+    fn parses_the_committed_manifest() {
+        let manifest: Manifest = serde_json::from_str(MANIFEST).unwrap();
+        let version = manifest
+            .versions
+            .iter()
+            .find(|v| v.version == "22.0.0")
+            .expect("22.0.0 is listed");
+
+        assert_eq!(
+            manifest.html_paths(version),
+            vec![std::path::Path::new("keycloak/22.0.0.html")]
+        );
+        assert_eq!(
+            manifest.output_path(version, OutputFormat::Yaml),
+            std::path::Path::new("keycloak/22.0.0.yml")
+        );
+        assert_eq!(
+            manifest.license_path(),
+            std::path::Path::new("keycloak/LICENSE.txt")
+        );
+
+        let patched = manifest
+            .versions
+            .iter()
+            .find(|v| v.version == "12.0-patched")
+            .expect("12.0-patched is listed");
+        assert!(manifest.html_paths(patched).is_empty());
+        assert_eq!(patched.output_formats(), &[OutputFormat::Yaml]);
+    }
+}
@@ -0,0 +1,121 @@
+use scraper::element_ref::ElementRef;
+
+/// Convert the documentation's prose to the CommonMark OpenAPI descriptions use
+///
+/// Code, emphasis, links and lists keep their meaning. Links within the page
+/// become plain text, as their anchors mean nothing outside it.
+pub fn from_html(element: &ElementRef<'_>) -> String {
+    let mut markdown = String::new();
+    push_children(element, &mut markdown);
+
+    let lines: Vec<&str> = markdown.lines().map(str::trim).collect();
+    let mut out = String::new();
+    for line in lines.iter() {
+        if line.is_empty() && (out.is_empty() || out.ends_with("\n\n")) {
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+fn push_children(element: &ElementRef<'_>, out: &mut String) {
+    for child in element.children() {
+        if let Some(child) = ElementRef::wrap(child) {
+            push_element(&child, out);
+        } else if let Some(text) = child.value().as_text() {
+            push_text(text, out);
+        }
+    }
+}
+
+fn push_element(element: &ElementRef<'_>, out: &mut String) {
+    match element.value().name() {
+        "code" | "tt" => {
+            out.push('`');
+            out.push_str(&element.text().collect::<String>());
+            out.push('`');
+        }
+        "strong" | "b" => wrap(element, "**", out),
+        "em" | "i" => wrap(element, "_", out),
+        "a" => match element.value().attr("href") {
+            Some(href) if !href.starts_with('#') => {
+                out.push('[');
+                push_children(element, out);
+                out.push_str("](");
+                out.push_str(href);
+                out.push(')');
+            }
+            _ => push_children(element, out),
+        },
+        "br" => out.push('\n'),
+        "li" => {
+            let mut item = String::new();
+            push_children(element, &mut item);
+            if !out.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str("- ");
+            out.push_str(&item.split_whitespace().collect::<Vec<_>>().join(" "));
+            out.push('\n');
+        }
+        "p" | "div" | "ul" | "ol" | "table" => {
+            out.push_str("\n\n");
+            push_children(element, out);
+            out.push_str("\n\n");
+        }
+        _ => push_children(element, out),
+    }
+}
+
+fn wrap(element: &ElementRef<'_>, marker: &str, out: &mut String) {
+    out.push_str(marker);
+    push_children(element, out);
+    out.push_str(marker);
+}
+
+/// Text with its runs of whitespace collapsed, as a browser would show it
+fn push_text(text: &str, out: &mut String) {
+    let mut words = text.split_whitespace().peekable();
+    if text.starts_with(char::is_whitespace) && words.peek().is_some() {
+        out.push(' ');
+    }
+    while let Some(word) = words.next() {
+        out.push_str(word);
+        if words.peek().is_some() {
+            out.push(' ');
+        }
+    }
+    if text.ends_with(char::is_whitespace) && !text.trim().is_empty() {
+        out.push(' ');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_html;
+    use scraper::{Html, Selector};
+
+    #[test]
+    fn converts_inline_markup() {
+        let fragment = Html::parse_fragment(
+            r##"<table><tr><td class="tableblock">
+                <p class="tableblock">Sets   the <code>realm</code>'s <strong>default</strong>
+                roles, see <a href="#RoleRepresentation">RoleRepresentation</a> and
+                <a href="https://www.keycloak.org/docs">the <em>guide</em></a>.</p>
+                <div class="ulist"><ul><li><p>one</p></li><li><p>two</p></li></ul></div>
+            </td></tr></table>"##,
+        );
+        let cell = fragment
+            .select(&Selector::parse("td").unwrap())
+            .next()
+            .unwrap();
+
+        assert_eq!(
+            from_html(&cell),
+            "Sets the `realm`'s **default** roles, see RoleRepresentation and \
+             [the _guide_](https://www.keycloak.org/docs).\n\n- one\n- two"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use openapiv3::{OpenAPI, PathItem, ReferenceOr};
+
+use crate::diagnostics::{Diagnostics, Location};
+use crate::error::TransformError;
+
+/// Merge the paths, tags and components of another definition into `spec`
+///
+/// Where both define the same operation, or different schemas of the same
+/// name, `spec` keeps its own and the conflict is reported.
+pub fn merge(spec: &mut OpenAPI, other: OpenAPI, diagnostics: &mut Diagnostics) {
+    for (path, path_item) in other.paths.paths {
+        match (spec.paths.paths.get_mut(&path), path_item) {
+            (None, path_item) => {
+                spec.paths.paths.insert(path, path_item);
+            }
+            (Some(ReferenceOr::Item(existing)), ReferenceOr::Item(path_item)) => {
+                merge_path_item(&path, existing, path_item, diagnostics);
+            }
+            (Some(_), _) => {
+                diagnostics.error(
+                    Default::default(),
+                    TransformError::ConflictingPath(path.clone()),
+                );
+            }
+        }
+    }
+
+    for tag in other.tags {
+        if !spec.tags.iter().any(|t| t.name == tag.name) {
+            spec.tags.push(tag);
+        }
+    }
+
+    if let Some(other_components) = other.components {
+        let components = spec.components.get_or_insert_with(Default::default);
+        for (name, schema) in other_components.schemas {
+            match components.schemas.get(&name) {
+                None => {
+                    components.schemas.insert(name, schema);
+                }
+                Some(existing) if same_schema(existing, &schema) => {}
+                Some(_) => {
+                    diagnostics.error(source(&schema), TransformError::ConflictingSchema(name));
+                }
+            }
+        }
+        for (name, scheme) in other_components.security_schemes {
+            components.security_schemes.entry(name).or_insert(scheme);
+        }
+    }
+}
+
+fn merge_path_item(
+    path: &str,
+    existing: &mut PathItem,
+    path_item: PathItem,
+    diagnostics: &mut Diagnostics,
+) {
+    if existing.parameters.is_empty() {
+        existing.parameters = path_item.parameters;
+    }
+
+    let operations = vec![
+        ("GET", &mut existing.get, path_item.get),
+        ("PUT", &mut existing.put, path_item.put),
+        ("POST", &mut existing.post, path_item.post),
+        ("DELETE", &mut existing.delete, path_item.delete),
+        ("OPTIONS", &mut existing.options, path_item.options),
+        ("HEAD", &mut existing.head, path_item.head),
+        ("PATCH", &mut existing.patch, path_item.patch),
+        ("TRACE", &mut existing.trace, path_item.trace),
+    ];
+    for (verb, slot, operation) in operations {
+        match (slot.as_ref(), operation) {
+            (_, None) => {}
+            (None, operation) => *slot = operation,
+            (Some(_), Some(operation)) => diagnostics.error(
+                Location::source(&operation.extensions),
+                TransformError::ConflictingPath(format!("{} {}", verb, path)),
+            ),
+        }
+    }
+}
+
+/// Schemas are the same if they only differ by where they were documented
+fn same_schema(a: &ReferenceOr<openapiv3::Schema>, b: &ReferenceOr<openapiv3::Schema>) -> bool {
+    match (a, b) {
+        (ReferenceOr::Item(a), ReferenceOr::Item(b)) => a.schema_kind == b.schema_kind,
+        (a, b) => a == b,
+    }
+}
+
+fn source(schema: &ReferenceOr<openapiv3::Schema>) -> Location {
+    match schema {
+        ReferenceOr::Item(schema) => Location::source(&schema.schema_data.extensions),
+        ReferenceOr::Reference { .. } => Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge;
+    use crate::diagnostics::Diagnostics;
+    use crate::error::TransformError;
+    use openapiv3::{OpenAPI, Operation, PathItem, ReferenceOr, Schema, SchemaKind, Type};
+
+    fn spec(path_item: PathItem, schema: Type) -> OpenAPI {
+        let mut spec = OpenAPI {
+            components: Some(Default::default()),
+            ..Default::default()
+        };
+        spec.paths
+            .paths
+            .insert("/{realm}/custom".into(), ReferenceOr::Item(path_item));
+        spec.components.as_mut().unwrap().schemas.insert(
+            "CustomRepresentation".into(),
+            ReferenceOr::Item(Schema {
+                schema_data: Default::default(),
+                schema_kind: SchemaKind::Type(schema),
+            }),
+        );
+        spec
+    }
+
+    #[test]
+    fn combines_operations_of_the_same_path() {
+        let mut diagnostics = Diagnostics::default();
+        let mut merged = spec(
+            PathItem {
+                get: Some(Operation::default()),
+                ..Default::default()
+            },
+            Type::Boolean {},
+        );
+        merge(
+            &mut merged,
+            spec(
+                PathItem {
+                    post: Some(Operation::default()),
+                    ..Default::default()
+                },
+                Type::Boolean {},
+            ),
+            &mut diagnostics,
+        );
+
+        assert!(diagnostics.is_empty());
+        match &merged.paths.paths["/{realm}/custom"] {
+            ReferenceOr::Item(path_item) => {
+                assert!(path_item.get.is_some() && path_item.post.is_some())
+            }
+            _ => panic!("Couldn't extract path"),
+        }
+    }
+
+    #[test]
+    fn reports_conflicts() {
+        let mut diagnostics = Diagnostics::default();
+        let get = || PathItem {
+            get: Some(Operation::default()),
+            ..Default::default()
+        };
+        let mut merged = spec(get(), Type::Boolean {});
+        merge(
+            &mut merged,
+            spec(get(), Type::String(Default::default())),
+            &mut diagnostics,
+        );
+
+        let errors: Vec<_> = diagnostics.iter().map(|d| &d.error).collect();
+        assert_eq!(
+            errors,
+            vec![
+                &TransformError::ConflictingPath("GET /{realm}/custom".into()),
+                &TransformError::ConflictingSchema("CustomRepresentation".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use clap::ValueEnum;
+use openapiv3::{OpenAPI, Parameter, PathItem, ReferenceOr, Schema, SchemaKind, Type};
+use serde::Deserialize;
+
+use crate::paths;
+
+/// The order paths, parameters, schemas, properties and tags are written in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Ordering {
+    /// Sorted, so definitions of different versions only differ by their API
+    #[default]
+    Canonical,
+    /// The order the documentation lists them in
+    Document,
+}
+
+/// Put a definition into canonical order
+///
+/// Paths, tags, schemas, properties and required properties are sorted by
+/// name. Path parameters follow the order they appear in the path, and
+/// operation parameters are sorted by location then name.
+pub fn canonicalize(spec: &mut OpenAPI) {
+    spec.paths.paths.sort_keys();
+    for (path, path_item) in spec.paths.paths.iter_mut() {
+        if let ReferenceOr::Item(path_item) = path_item {
+            canonicalize_path_item(path, path_item);
+        }
+    }
+
+    spec.tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if let Some(components) = &mut spec.components {
+        components.schemas.sort_keys();
+        for schema in components.schemas.values_mut() {
+            if let ReferenceOr::Item(schema) = schema {
+                canonicalize_schema(schema);
+            }
+        }
+    }
+}
+
+fn canonicalize_path_item(path: &str, path_item: &mut PathItem) {
+    path_item
+        .parameters
+        .sort_by_key(|parameter| match parameter {
+            ReferenceOr::Item(parameter) => (
+                path.find(&format!("{{{}}}", parameter.parameter_data_ref().name))
+                    .unwrap_or(path.len()),
+                parameter.parameter_data_ref().name.clone(),
+            ),
+            ReferenceOr::Reference { reference } => (path.len(), reference.clone()),
+        });
+
+    for operation in paths::operations_mut(path_item) {
+        operation
+            .parameters
+            .sort_by_key(|parameter| match parameter {
+                ReferenceOr::Item(parameter) => (
+                    location_rank(parameter),
+                    parameter.parameter_data_ref().name.clone(),
+                ),
+                ReferenceOr::Reference { reference } => (usize::MAX, reference.clone()),
+            });
+        if let Some(ReferenceOr::Item(request_body)) = &mut operation.request_body {
+            for media_type in request_body.content.values_mut() {
+                if let Some(ReferenceOr::Item(schema)) = &mut media_type.schema {
+                    canonicalize_schema(schema);
+                }
+            }
+        }
+    }
+}
+
+fn location_rank(parameter: &Parameter) -> usize {
+    match parameter {
+        Parameter::Path { .. } => 0,
+        Parameter::Query { .. } => 1,
+        Parameter::Header { .. } => 2,
+        Parameter::Cookie { .. } => 3,
+    }
+}
+
+fn canonicalize_schema(schema: &mut Schema) {
+    match &mut schema.schema_kind {
+        SchemaKind::Type(Type::Object(object)) => {
+            object.properties.sort_keys();
+            object.required.sort();
+            for property in object.properties.values_mut() {
+                if let ReferenceOr::Item(property) = property {
+                    canonicalize_schema(property);
+                }
+            }
+        }
+        SchemaKind::Type(Type::Array(array)) => {
+            if let Some(ReferenceOr::Item(items)) = &mut array.items {
+                canonicalize_schema(items);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::canonicalize;
+    use crate::{transform, Options, OutputFormat};
+    use openapiv3::{ObjectType, OpenAPI, ReferenceOr, Schema, SchemaKind, Type};
+
+    const HTML: &str = r#"
+        <div id="header"><h1>Keycloak Admin REST API</h1></div>
+        <div id="preamble"><div class="sectionbody"><div class="paragraph"><p>API</p></div></div></div>
+        <h3 id="_version_information"></h3>
+        <div class="paragraph"><p>Version: 22.0.0</p></div>
+        <h2 id="_resources">Resources</h2>
+        <div class="sectionbody">
+            <div class="sect2"><h3>Users</h3>
+                <div class="sect3"><h4>GET /admin/realms/{realm}/users</h4>
+                    <div class="sect4">
+                        <h6>Path Parameters</h6>
+                        <table><thead><tr><th>Name</th><th>Description</th></tr></thead>
+                        <tbody><tr><td>realm</td><td>realm name</td></tr></tbody></table>
+                        <h5>Responses</h5>
+                        <table><tbody><tr><td>200</td><td>success</td><td>List[UserRepresentation]</td></tr></tbody></table>
+                    </div>
+                </div>
+            </div>
+        </div>
+        <h2 id="models">Models</h2>
+        <div class="sectionbody">
+            <div class="sect2"><h3>UserRepresentation</h3>
+                <table><tbody>
+                    <tr><td><p><strong>username</strong></p></td><td><p>String</p></td></tr>
+                    <tr><td><p><strong>groups</strong></p></td><td><p>List of [GroupRepresentation]</p></td></tr>
+                    <tr><td><p><strong>attributes</strong></p></td><td><p>Map of [array]</p></td></tr>
+                </tbody></table>
+            </div>
+            <div class="sect2"><h3>GroupRepresentation</h3>
+                <table><tbody><tr><td><p><strong>name</strong></p></td><td><p>String</p></td></tr></tbody></table>
+            </div>
+        </div>
+    "#;
+
+    #[test]
+    fn runs_are_byte_identical() {
+        let render = || {
+            OutputFormat::Json
+                .render(&transform(HTML, &Options::default()).unwrap().spec)
+                .unwrap()
+        };
+
+        let json = render();
+        assert_eq!(json, render());
+        assert!(json.find("\"GroupRepresentation\": {") < json.find("\"UserRepresentation\": {"));
+    }
+
+    #[test]
+    fn sorts_schemas_and_properties() {
+        let mut object = ObjectType::default();
+        for name in ["realm", "enabled", "id"].iter() {
+            object.properties.insert(
+                name.to_string(),
+                ReferenceOr::boxed_item(Schema {
+                    schema_data: Default::default(),
+                    schema_kind: SchemaKind::Type(Type::Boolean {}),
+                }),
+            );
+        }
+        let schema = ReferenceOr::Item(Schema {
+            schema_data: Default::default(),
+            schema_kind: SchemaKind::Type(Type::Object(object)),
+        });
+        let mut spec = OpenAPI {
+            components: Some(Default::default()),
+            ..Default::default()
+        };
+        let schemas = &mut spec.components.as_mut().unwrap().schemas;
+        schemas.insert("UserRepresentation".into(), schema.clone());
+        schemas.insert("RealmRepresentation".into(), schema);
+
+        canonicalize(&mut spec);
+
+        let schemas = &spec.components.unwrap().schemas;
+        assert_eq!(
+            schemas.keys().collect::<Vec<_>>(),
+            vec!["RealmRepresentation", "UserRepresentation"]
+        );
+        match &schemas["UserRepresentation"] {
+            ReferenceOr::Item(Schema {
+                schema_kind: SchemaKind::Type(Type::Object(object)),
+                ..
+            }) => assert_eq!(
+                object.properties.keys().collect::<Vec<_>>(),
+                vec!["enabled", "id", "realm"]
+            ),
+            _ => panic!("Couldn't extract schema"),
+        }
+    }
+}
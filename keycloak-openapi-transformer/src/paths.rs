@@ -1,89 +1,55 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use heck::ToLowerCamelCase;
-use scraper::Selector;
+use indexmap::IndexSet;
+
+use crate::ir;
 
 mod operation;
 mod parameters;
 mod response;
-mod verb_path;
-
-use verb_path::VerbPath;
-
-lazy_static! {
-    static ref TAG_SECTION_SELECTOR: Selector =
-        Selector::parse("#_resources + .sectionbody > .sect2").unwrap();
-    static ref TAG_TITLE_SELECTOR: Selector = Selector::parse("h3").unwrap();
-    static ref PATH_SECTION_SELECTOR: Selector = Selector::parse(".sect3").unwrap();
-    static ref SUMMARY_SELECTOR: Selector = Selector::parse("h4:first-child").unwrap();
-    static ref PRE_PATH_SELECTOR: Selector = Selector::parse("pre").unwrap();
-}
+pub mod verb_path;
 
-pub fn paths(document: &scraper::html::Html) -> (openapiv3::Paths, HashSet<String>) {
+/// Emit an API's endpoints as OpenAPI paths, along with the tags they use
+pub fn paths(api: &ir::Api) -> (openapiv3::Paths, IndexSet<String>) {
     let mut paths = openapiv3::Paths::default();
-    let mut tag_set = HashSet::new();
+    let mut tag_set = IndexSet::new();
+    // Where each path first appears in the document
+    let mut document_order: HashMap<&str, usize> = HashMap::new();
     let mut id_state_map: HashMap<String, usize> = HashMap::new();
 
-    // First pass to find operation id collisions
-    for tag_section in document.select(&TAG_SECTION_SELECTOR) {
-        let sections = tag_section
-            .select(&PATH_SECTION_SELECTOR)
-            .collect::<Vec<_>>();
-
-        for section in sections.iter().rev() {
-            let verb_path = verb_path_split(section);
-
-            if verb_path.unrepresentable() {
-                continue;
-            };
-
-            for id in generate_operation_ids(&verb_path.verb, &verb_path.path()) {
-                let seen: usize = *id_state_map.get(&id).unwrap_or(&0);
-
-                id_state_map.insert(id.clone(), seen + 1);
-            }
+    // Find operation id collisions
+    for endpoint in api.endpoints() {
+        for id in generate_operation_ids(&endpoint.verb, &endpoint.path) {
+            *id_state_map.entry(id).or_insert(0) += 1;
         }
     }
 
-    for tag_section in document.select(&TAG_SECTION_SELECTOR) {
-        let tag = tag_section
-            .select(&TAG_TITLE_SELECTOR)
-            .next()
-            .unwrap()
-            .text()
-            .collect::<String>();
-
-        let sections = tag_section
-            .select(&PATH_SECTION_SELECTOR)
-            .collect::<Vec<_>>();
-        for section in sections.iter().rev() {
-            let verb_path = verb_path_split(section);
-
-            if verb_path.unrepresentable() {
-                continue;
-            };
+    for resource in api.resources.iter() {
+        for endpoint in resource.endpoints.iter().rev() {
+            document_order.insert(&endpoint.path, endpoint.position);
             if let openapiv3::ReferenceOr::Item(path_item) =
-                paths.paths.entry(verb_path.path()).or_insert_with(|| {
+                paths.paths.entry(endpoint.path.clone()).or_insert_with(|| {
                     openapiv3::ReferenceOr::Item(openapiv3::PathItem {
-                        parameters: parameters::parse_path(section, &verb_path),
+                        parameters: parameters::path_params(&endpoint.path_params),
                         ..Default::default()
                     })
                 })
             {
-                let mut operation = operation::parse(section);
+                let mut operation = operation::emit(endpoint);
 
-                operation.operation_id = generate_operation_ids(&verb_path.verb, &verb_path.path())
+                operation.operation_id = generate_operation_ids(&endpoint.verb, &endpoint.path)
                     .into_iter()
                     .find(|id| match id_state_map.get(id) {
                         Some(seen) => *seen < 2,
                         None => true,
                     });
 
-                tag_set.insert(tag.clone());
+                tag_set.insert(resource.tag.clone());
 
-                operation.tags = vec![tag.clone()];
+                operation.tags = vec![resource.tag.clone()];
                 let operation = Some(operation);
-                match verb_path.verb.as_ref() {
+                match endpoint.verb.as_ref() {
                     "DELETE" => {
                         path_item.delete = operation;
                     }
@@ -102,26 +68,51 @@ pub fn paths(document: &scraper::html::Html) -> (openapiv3::Paths, HashSet<Strin
                     "OPTIONS" => {
                         path_item.options = operation;
                     }
-                    _ => panic!("Unexpected HTTP verb: {:?}", verb_path.verb),
+                    // VerbPath only parses the verbs above
+                    _ => {}
                 };
             }
         }
     }
-    paths.paths.sort_keys();
+    paths
+        .paths
+        .sort_by(|a, _, b, _| document_order[a.as_str()].cmp(&document_order[b.as_str()]));
 
     (paths, tag_set)
 }
 
-fn verb_path_split(section: &scraper::element_ref::ElementRef<'_>) -> VerbPath {
-    section
-        .select(&PRE_PATH_SELECTOR)
-        .next()
-        .or_else(|| section.select(&SUMMARY_SELECTOR).next())
-        .unwrap()
-        .text()
-        .collect::<String>()
-        .parse()
-        .unwrap()
+/// The operations of a path item, in the order of its method fields
+pub fn operations(path_item: &openapiv3::PathItem) -> impl Iterator<Item = &openapiv3::Operation> {
+    vec![
+        &path_item.get,
+        &path_item.put,
+        &path_item.post,
+        &path_item.delete,
+        &path_item.options,
+        &path_item.head,
+        &path_item.patch,
+        &path_item.trace,
+    ]
+    .into_iter()
+    .filter_map(Option::as_ref)
+}
+
+/// The operations of a path item, in the order of its method fields, to change
+pub fn operations_mut(
+    path_item: &mut openapiv3::PathItem,
+) -> impl Iterator<Item = &mut openapiv3::Operation> {
+    vec![
+        &mut path_item.get,
+        &mut path_item.put,
+        &mut path_item.post,
+        &mut path_item.delete,
+        &mut path_item.options,
+        &mut path_item.head,
+        &mut path_item.patch,
+        &mut path_item.trace,
+    ]
+    .into_iter()
+    .filter_map(Option::as_mut)
 }
 
 /// Generate a list of possible operation ids starting with shortest
@@ -184,17 +175,13 @@ fn generate_operation_ids(verb: &str, path: &str) -> Vec<String> {
 
 #[cfg(test)]
 mod tests {
-    const HTML: &str = include_str!("../../keycloak/22.0.0.html");
-
     mod parameters {
-        use super::super::paths;
-        use super::HTML;
+        use crate::fixture::STAGES;
         use openapiv3::ReferenceOr;
-        use scraper::Html;
 
         #[test]
         fn correctly_parses_when_there_are_no_parameters() {
-            let paths = paths(&Html::parse_document(HTML)).0.paths;
+            let paths = &STAGES.paths.paths;
             let path = if let Some(ReferenceOr::Item(path)) = paths.get("/") {
                 path
             } else {
@@ -205,7 +192,7 @@ mod tests {
 
         #[test]
         fn correctly_parses_when_there_are_three_parameters() {
-            let paths = paths(&Html::parse_document(HTML)).0.paths;
+            let paths = &STAGES.paths.paths;
             let path = if let Some(ReferenceOr::Item(path)) =
                 paths.get("/{realm}/client-scopes/{id}/protocol-mappers/protocol/{protocol}")
             {
@@ -229,7 +216,7 @@ mod tests {
 
         #[test]
         fn correctly_parse_when_there_are_repeating_ids_parameters() {
-            let paths = paths(&Html::parse_document(HTML)).0.paths;
+            let paths = &STAGES.paths.paths;
             let path = if let Some(ReferenceOr::Item(path)) =
                 paths.get("/{realm}/clients/{id1}/protocol-mappers/models/{id2}")
             {
@@ -253,7 +240,7 @@ mod tests {
 
         #[test]
         fn adds_descriptions_when_not_always_present() {
-            let paths = paths(&Html::parse_document(HTML)).0.paths;
+            let paths = &STAGES.paths.paths;
             let path_item = if let Some(ReferenceOr::Item(path)) =
                 paths.get("/{realm}/authentication/client-authenticator-providers")
             {
@@ -273,13 +260,11 @@ mod tests {
     }
 
     mod operations {
-        use super::super::paths;
-        use super::HTML;
+        use crate::fixture::STAGES;
         use openapiv3::ReferenceOr;
-        use scraper::Html;
 
         fn get_path(path: &str) -> openapiv3::PathItem {
-            let paths = paths(&Html::parse_document(HTML)).0.paths;
+            let paths = &STAGES.paths.paths;
             if let Some(ReferenceOr::Item(path)) = paths.get(path) {
                 path.clone()
             } else {
@@ -315,7 +300,7 @@ mod tests {
         // Additionally, it couldn't be defined as sub paths can't be substituted in
         #[test]
         fn does_not_parse_the_any_path() {
-            let paths = paths(&Html::parse_document(HTML)).0.paths;
+            let paths = &STAGES.paths.paths;
             assert!(!paths.contains_key("/{any}"));
         }
 
@@ -343,4 +328,66 @@ mod tests {
             );
         }
     }
+
+    mod diagnostics {
+        use super::super::paths;
+        use crate::diagnostics::{Diagnostics, Severity};
+        use crate::error::TransformError;
+        use crate::index::Index;
+        use crate::layout::Layout;
+        use crate::scrape::scrape;
+        use scraper::Html;
+
+        const HTML: &str = r#"
+            <h2 id="_resources">Resources</h2>
+            <div class="sectionbody">
+                <div class="sect2">
+                    <h3>Realms Admin</h3>
+                    <div class="sect3">
+                        <h4 id="_realms_any">GET /admin/realms/{any}</h4>
+                    </div>
+                    <div class="sect3">
+                        <h4 id="_realms_trace">TRACE /admin/realms/{realm}</h4>
+                    </div>
+                    <div class="sect3">
+                        <h4 id="_realms_nothing"></h4>
+                    </div>
+                </div>
+            </div>
+        "#;
+
+        #[test]
+        fn reports_sections_it_cannot_transform() {
+            let document = Html::parse_document(HTML);
+            let mut diagnostics = Diagnostics::default();
+            let index = Index::new(&document, Layout::current(), &mut diagnostics);
+            let (paths, _) = paths(&scrape(&index, &mut diagnostics));
+
+            assert!(paths.paths.is_empty());
+            let errors: Vec<_> = diagnostics
+                .iter()
+                .map(|d| (d.severity, d.location.anchor.as_deref(), &d.error))
+                .collect();
+            assert_eq!(
+                errors,
+                vec![
+                    (
+                        Severity::Info,
+                        Some("_realms_any"),
+                        &TransformError::UnrepresentablePath("GET /{any}".into())
+                    ),
+                    (
+                        Severity::Error,
+                        Some("_realms_trace"),
+                        &TransformError::UnexpectedHttpVerb("TRACE".into())
+                    ),
+                    (
+                        Severity::Error,
+                        Some("_realms_nothing"),
+                        &TransformError::MalformedVerbPath("".into())
+                    ),
+                ]
+            );
+        }
+    }
 }
@@ -1,179 +1,86 @@
 use super::super::components::schemas::parse_type;
-use crate::{paths::verb_path::VerbPath, table::parse_table_rows};
+use crate::ir;
 use indexmap::IndexMap;
-use openapiv3::{MediaType, Parameter, ParameterData, ReferenceOr, RequestBody};
-use regex::Regex;
-use scraper::Selector;
+use openapiv3::{
+    MediaType, ObjectType, Parameter, ParameterData, ReferenceOr, RequestBody, Schema, SchemaKind,
+    Type,
+};
 
-lazy_static! {
-    static ref PATH_PARAM_REGEX: Regex = Regex::new(r"\{([^}]+)}").unwrap();
-    static ref PATH_PARAMS_TABLE_SELECTOR: Selector =
-        Selector::parse("h6[id^=_path_parameters] + table").unwrap();
-    static ref QUERY_PARAMS_TABLE_SELECTOR: Selector =
-        Selector::parse("h6[id^=_query_parameters] + table").unwrap();
-    static ref BODY_PARAMS_TABLE_SELECTOR: Selector =
-        Selector::parse("h6[id^=_body_parameter] + table").unwrap();
-}
-
-pub fn parse_body_param(
-    section: &scraper::element_ref::ElementRef<'_>,
-) -> Option<ReferenceOr<RequestBody>> {
-    match parse_table_rows(section, &BODY_PARAMS_TABLE_SELECTOR).first() {
-        None => None,
-        Some(row) => {
-            let type_string = &row["Description"];
-            let mut content = IndexMap::new();
-            content.insert(
-                "application/json".to_string(),
-                MediaType {
-                    schema: Some(parse_type(type_string)),
-                    ..Default::default()
-                },
-            );
-            Some(ReferenceOr::Item(RequestBody {
-                description: Some(row["Description"].clone()),
-                required: false,
-                content,
-                extensions: Default::default(),
-            }))
-        }
+fn parameter_data(param: &ir::Param, required: bool) -> ParameterData {
+    ParameterData {
+        name: param.name.clone(),
+        description: param.description.clone(),
+        required,
+        deprecated: None,
+        format: openapiv3::ParameterSchemaOrContent::Schema(parse_type("string")),
+        example: None,
+        examples: Default::default(),
+        explode: Default::default(),
+        extensions: Default::default(),
     }
 }
 
-pub fn parse_query_params(
-    section: &scraper::element_ref::ElementRef<'_>,
-) -> Vec<ReferenceOr<Parameter>> {
-    let mut out = Vec::new();
-
-    for row in parse_table_rows(section, &QUERY_PARAMS_TABLE_SELECTOR) {
-        out.push(ReferenceOr::Item(Parameter::Query {
-            parameter_data: openapiv3::ParameterData {
-                name: row["Name"].split('\n').next().unwrap().to_string(),
-                description: if row["Description"].is_empty() {
-                    None
-                } else {
-                    Some(row["Description"].clone())
-                },
-                required: false,
-                deprecated: None,
-                format: openapiv3::ParameterSchemaOrContent::Schema(openapiv3::ReferenceOr::Item(
-                    openapiv3::Schema {
-                        schema_data: Default::default(),
-                        schema_kind: openapiv3::SchemaKind::Type(openapiv3::Type::String(
-                            openapiv3::StringType::default(),
-                        )),
-                    },
-                )),
-                example: None,
-                examples: Default::default(),
-                explode: Default::default(),
-                extensions: Default::default(),
-            },
-            allow_reserved: false,
-            style: Default::default(),
-            allow_empty_value: None,
-        }));
+/// The body's type, combined with the properties it must have when it requires more
+fn body_schema(body: &ir::Body) -> ReferenceOr<Schema> {
+    if body.required.is_empty() {
+        return parse_type(&body.raw_type);
     }
-
-    out
+    ReferenceOr::Item(Schema {
+        schema_data: Default::default(),
+        schema_kind: SchemaKind::AllOf {
+            all_of: vec![
+                parse_type(&body.raw_type),
+                ReferenceOr::Item(Schema {
+                    schema_data: Default::default(),
+                    schema_kind: SchemaKind::Type(Type::Object(ObjectType {
+                        required: body.required.clone(),
+                        ..Default::default()
+                    })),
+                }),
+            ],
+        },
+    })
 }
-pub fn parse_path_params(
-    section: &scraper::element_ref::ElementRef<'_>,
-) -> Vec<ReferenceOr<Parameter>> {
-    let mut out = Vec::new();
 
-    for row in parse_table_rows(section, &PATH_PARAMS_TABLE_SELECTOR) {
-        out.push(ReferenceOr::Item(Parameter::Path {
-            parameter_data: openapiv3::ParameterData {
-                name: row["Name"].split('\n').next().unwrap().to_string(),
-                description: if row["Description"].is_empty() {
-                    None
-                } else {
-                    Some(row["Description"].clone())
-                },
-                required: true,
-                deprecated: None,
-                format: openapiv3::ParameterSchemaOrContent::Schema(openapiv3::ReferenceOr::Item(
-                    openapiv3::Schema {
-                        schema_data: Default::default(),
-                        schema_kind: openapiv3::SchemaKind::Type(openapiv3::Type::String(
-                            openapiv3::StringType::default(),
-                        )),
-                    },
-                )),
-                example: None,
-                examples: Default::default(),
-                explode: Default::default(),
-                extensions: Default::default(),
-            },
-            style: Default::default(),
-        }));
-    }
-
-    out
+pub fn body_param(body: &ir::Body) -> ReferenceOr<RequestBody> {
+    let mut content = IndexMap::new();
+    content.insert(
+        "application/json".to_string(),
+        MediaType {
+            schema: Some(body_schema(body)),
+            ..Default::default()
+        },
+    );
+    ReferenceOr::Item(RequestBody {
+        description: Some(body.raw_type.clone()),
+        required: false,
+        content,
+        extensions: Default::default(),
+    })
 }
 
-pub fn parse_path(
-    section: &scraper::element_ref::ElementRef<'_>,
-    verb_path: &VerbPath,
-) -> Vec<ReferenceOr<Parameter>> {
-    let mut params = parse_path_params(section);
-
-    if let Some(repeats) = verb_path.repeating_ids() {
-        params.retain(|p| {
-            if let ReferenceOr::Item(Parameter::Path {
-                parameter_data: ParameterData { name, .. },
-                ..
-            }) = p
-            {
-                name != "id"
-            } else {
-                true
-            }
-        });
-        params.extend((1..=repeats).map(|i| {
-            ReferenceOr::Item(Parameter::Path {
-                parameter_data: ParameterData {
-                    name: format!("id{}", i),
-                    required: true,
-                    format: openapiv3::ParameterSchemaOrContent::Schema(parse_type("string")),
-                    description: None,
-                    deprecated: None,
-                    example: None,
-                    examples: Default::default(),
-                    explode: Default::default(),
-                    extensions: Default::default(),
-                },
+pub fn query_params(params: &[ir::Param]) -> Vec<ReferenceOr<Parameter>> {
+    params
+        .iter()
+        .map(|param| {
+            ReferenceOr::Item(Parameter::Query {
+                parameter_data: parameter_data(param, false),
+                allow_reserved: false,
                 style: Default::default(),
+                allow_empty_value: None,
             })
-        }))
-    }
-
-    for (index, cap) in PATH_PARAM_REGEX
-        .captures_iter(&verb_path.path())
-        .enumerate()
-        .take(params.len())
-    {
-        let path_var = &cap[1];
-        let position = params
-            .iter()
-            .enumerate()
-            .find(|(_, param)| {
-                if let ReferenceOr::Item(Parameter::Path {
-                    parameter_data: openapiv3::ParameterData { name, .. },
-                    ..
-                }) = param
-                {
-                    name == path_var
-                } else {
-                    false
-                }
-            })
-            .map(|(i, _)| i);
-        if let Some(position) = position {
-            params.swap(index, position);
-        }
-    }
+        })
+        .collect()
+}
 
+pub fn path_params(params: &[ir::Param]) -> Vec<ReferenceOr<Parameter>> {
     params
+        .iter()
+        .map(|param| {
+            ReferenceOr::Item(Parameter::Path {
+                parameter_data: parameter_data(param, true),
+                style: Default::default(),
+            })
+        })
+        .collect()
 }
@@ -1,19 +1,28 @@
 use std::str::FromStr;
 
+use crate::error::TransformError;
+
+/// The verbs an OpenAPI path item has an operation slot for
+const HTTP_VERBS: [&str; 6] = ["DELETE", "GET", "PATCH", "POST", "PUT", "OPTIONS"];
+
 pub struct VerbPath {
     pub verb: String,
     path: String,
 }
 
 impl FromStr for VerbPath {
-    type Err = ();
+    type Err = TransformError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || TransformError::MalformedVerbPath(s.trim().to_string());
         let mut split = s.split_whitespace();
-        let verb = split.next().ok_or(())?.to_string();
+        let verb = split.next().ok_or_else(malformed)?.to_string();
+        if !HTTP_VERBS.contains(&verb.as_str()) {
+            return Err(TransformError::UnexpectedHttpVerb(verb));
+        }
         let mut path = split
             .next()
-            .ok_or(())?
+            .ok_or_else(malformed)?
             .to_string()
             .replace("/admin/realms", "");
 
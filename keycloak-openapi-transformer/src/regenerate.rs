@@ -0,0 +1,359 @@
+use std::fs;
+use std::io;
+use std::process::ExitCode;
+
+use keycloak_openapi_transformer::diff::diff;
+use keycloak_openapi_transformer::{Diagnostics, DiagnosticsFormat, OutputFormat};
+use rayon::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cache::{self, Checksums};
+use crate::cli::{DiagnosticsArgs, RegenerateArgs};
+use crate::manifest::{Manifest, Version};
+
+pub fn run(args: &RegenerateArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let manifest = Manifest::load(&args.manifest)?;
+    let checksums = manifest.load_checksums()?;
+    // Zero threads lets rayon use one per CPU
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.jobs.unwrap_or(0))
+        .build()?;
+
+    if args.check {
+        let outcomes = pool.install(|| {
+            each_version(&manifest, |version, log| {
+                check(&manifest, &checksums, version, log)
+            })
+        });
+        let (matches, diagnostics) = report(&manifest, outcomes, &args.diagnostics)?;
+        let mismatched = matches.iter().filter(|matches| !*matches).count();
+        if mismatched > 0 {
+            return Err(format!(
+                "{} of {} versions differ from a fresh transform",
+                mismatched,
+                manifest.versions.len()
+            )
+            .into());
+        }
+        return Ok(crate::exit_code(&diagnostics, args.deny_warnings));
+    }
+
+    let transformer = transformer_checksum()?;
+    let outcomes = pool.install(|| {
+        each_version(&manifest, |version, log| {
+            regenerate(
+                &manifest,
+                &checksums,
+                version,
+                &transformer,
+                args.force,
+                log,
+            )
+        })
+    });
+    let (_, diagnostics) = report(&manifest, outcomes, &args.diagnostics)?;
+
+    Ok(crate::exit_code(&diagnostics, args.deny_warnings))
+}
+
+/// Messages about one version, held back until every version is done so the
+/// output doesn't depend on which version finished first
+#[derive(Debug, Default)]
+struct Log {
+    stdout: Vec<String>,
+    stderr: Vec<String>,
+    diagnostics: Diagnostics,
+}
+
+type Outcome<T> = (Log, Result<T, String>);
+
+/// Run `job` for every version in the manifest across the current thread pool,
+/// returning the outcomes in manifest order
+fn each_version<T, F>(manifest: &Manifest, job: F) -> Vec<Outcome<T>>
+where
+    T: Send,
+    F: Fn(&Version, &mut Log) -> Result<T, Box<dyn std::error::Error>> + Sync,
+{
+    manifest
+        .versions
+        .par_iter()
+        .map(|version| {
+            let mut log = Log::default();
+            let result = job(version, &mut log).map_err(|e| format!("{}: {}", version.version, e));
+            (log, result)
+        })
+        .collect()
+}
+
+/// One version's diagnostics, as `--diagnostics-format json` writes them
+#[derive(Serialize)]
+struct VersionDiagnostics<'a> {
+    version: &'a str,
+    diagnostics: &'a Diagnostics,
+}
+
+/// Print every version's log in manifest order and write their diagnostics,
+/// failing if any version failed
+///
+/// Returns the diagnostics of every version together, which decide how the
+/// command exits.
+fn report<T>(
+    manifest: &Manifest,
+    outcomes: Vec<Outcome<T>>,
+    args: &DiagnosticsArgs,
+) -> Result<(Vec<T>, Diagnostics), Box<dyn std::error::Error>> {
+    let total = outcomes.len();
+    let mut values = Vec::with_capacity(total);
+    let mut failed = Vec::new();
+    let mut logs = Vec::with_capacity(total);
+
+    for (version, (log, result)) in manifest.versions.iter().zip(outcomes) {
+        for line in log.stdout.iter() {
+            println!("{}", line);
+        }
+        for line in log.stderr.iter() {
+            eprintln!("{}", line);
+        }
+        match result {
+            Ok(value) => values.push(value),
+            Err(e) => failed.push(e),
+        }
+        logs.push((version, log.diagnostics));
+    }
+
+    let rendered = match args.diagnostics_format {
+        DiagnosticsFormat::Text => logs
+            .iter()
+            .flat_map(|(version, diagnostics)| {
+                diagnostics
+                    .iter()
+                    .map(move |diagnostic| format!("{}: {}\n", version.version, diagnostic))
+            })
+            .collect(),
+        DiagnosticsFormat::Json => format!(
+            "{}\n",
+            serde_json::to_string_pretty(
+                &logs
+                    .iter()
+                    .map(|(version, diagnostics)| VersionDiagnostics {
+                        version: &version.version,
+                        diagnostics,
+                    })
+                    .collect::<Vec<_>>()
+            )?
+        ),
+    };
+    args.write_rendered(&rendered)?;
+
+    for e in failed.iter() {
+        eprintln!("{}", e);
+    }
+    if !failed.is_empty() {
+        return Err(format!("{} of {} versions failed", failed.len(), total).into());
+    }
+
+    let mut diagnostics = Diagnostics::default();
+    for (_, version_diagnostics) in logs {
+        diagnostics.extend(version_diagnostics);
+    }
+    Ok((values, diagnostics))
+}
+
+fn regenerate(
+    manifest: &Manifest,
+    checksums: &Checksums,
+    version: &Version,
+    transformer: &str,
+    force: bool,
+    log: &mut Log,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !force && up_to_date(manifest, checksums, version, transformer)? {
+        log.stderr.push(format!("{}: up to date", version.version));
+        return Ok(());
+    }
+
+    let definition = definition(manifest, checksums, version, log)?;
+
+    for format in version.output_formats() {
+        fs::write(
+            manifest.output_path(version, *format),
+            format.render(&definition)?,
+        )?;
+    }
+    record_stamp(manifest, checksums, version, transformer)?;
+    log.stderr.push(format!("{}: regenerated", version.version));
+
+    Ok(())
+}
+
+/// Compare a version's committed definitions with what the current transformer
+/// produces, returning whether they match
+fn check(
+    manifest: &Manifest,
+    checksums: &Checksums,
+    version: &Version,
+    log: &mut Log,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let definition = definition(manifest, checksums, version, log)?;
+    let mut matches = true;
+
+    for format in version.output_formats() {
+        let path = manifest.output_path(version, *format);
+        let rendered = format.render(&definition)?;
+        let committed = match fs::read_to_string(&path) {
+            Ok(committed) => committed,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                log.stdout.push(format!(
+                    "{}: {} is missing",
+                    version.version,
+                    path.display()
+                ));
+                matches = false;
+                continue;
+            }
+            Err(e) => return Err(format!("{}: {}", path.display(), e).into()),
+        };
+        if committed == rendered {
+            continue;
+        }
+        matches = false;
+
+        if *format == OutputFormat::Json {
+            let committed: Value = serde_json::from_str(&committed)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            let differences = diff(&committed, &definition);
+            if differences.is_empty() {
+                log.stdout.push(format!(
+                    "{}: {} differs only in formatting",
+                    version.version,
+                    path.display()
+                ));
+                continue;
+            }
+            log.stdout.push(format!(
+                "{}: {} has {} differences",
+                version.version,
+                path.display(),
+                differences.len()
+            ));
+            for difference in differences {
+                log.stdout.push(format!("  {}", difference));
+            }
+        } else {
+            log.stdout
+                .push(format!("{}: {} differs", version.version, path.display()));
+        }
+    }
+
+    if matches {
+        log.stderr.push(format!("{}: matches", version.version));
+    }
+
+    Ok(matches)
+}
+
+/// The version's definition, transformed from its documentation or read from
+/// its committed JSON when it has none
+fn definition(
+    manifest: &Manifest,
+    checksums: &Checksums,
+    version: &Version,
+    log: &mut Log,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    if version.html.is_none() {
+        let path = manifest.output_path(version, OutputFormat::Json);
+        let committed =
+            fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        return Ok(
+            serde_json::from_str(&committed).map_err(|e| format!("{}: {}", path.display(), e))?
+        );
+    }
+
+    let htmls = manifest.read_documentation(version, checksums)?;
+    match keycloak_openapi_transformer::transform_all(&htmls, &manifest.options(version)) {
+        Ok(transformed) => {
+            log.diagnostics.extend(transformed.diagnostics);
+            Ok(serde_json::to_value(&transformed.spec)?)
+        }
+        Err(diagnostics) => {
+            log.diagnostics.extend(diagnostics);
+            Err("could not produce a definition".into())
+        }
+    }
+}
+
+/// A checksum of the running transformer's executable
+///
+/// Any change to the transformer's code or dependencies changes it, not only a
+/// new release, so every version is regenerated by the rebuilt transformer.
+fn transformer_checksum() -> io::Result<String> {
+    Ok(cache::sha256(&fs::read(std::env::current_exe()?)?))
+}
+
+/// A checksum of everything a version's definitions depend on: the
+/// transformer, the options, and the contents of its inputs and outputs
+///
+/// Recorded after regenerating so that a version whose documentation, switches
+/// or definitions changed, or that was regenerated by a different build of the
+/// transformer, is regenerated again. `None` when an input or output is missing.
+fn stamp(
+    manifest: &Manifest,
+    checksums: &Checksums,
+    version: &Version,
+    transformer: &str,
+) -> io::Result<Option<String>> {
+    let inputs = match version.html {
+        Some(_) => manifest.documentation_paths(version, checksums),
+        None => vec![manifest.output_path(version, OutputFormat::Json)],
+    };
+    let outputs = version
+        .output_formats()
+        .iter()
+        .map(|format| manifest.output_path(version, *format));
+
+    let mut lines = vec![
+        transformer.to_string(),
+        format!("{:?}", manifest.options(version)),
+    ];
+    for path in inputs.into_iter().chain(outputs) {
+        match fs::read(&path) {
+            Ok(contents) => lines.push(cache::sha256(&contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(Some(cache::sha256(lines.join("\n").as_bytes())))
+}
+
+/// Whether the version's definitions were regenerated from exactly its current
+/// inputs, options and transformer, and haven't been changed since
+fn up_to_date(
+    manifest: &Manifest,
+    checksums: &Checksums,
+    version: &Version,
+    transformer: &str,
+) -> io::Result<bool> {
+    let recorded = match fs::read_to_string(manifest.stamp_path(version)) {
+        Ok(recorded) => recorded,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    Ok(stamp(manifest, checksums, version, transformer)?.as_deref() == Some(recorded.trim()))
+}
+
+fn record_stamp(
+    manifest: &Manifest,
+    checksums: &Checksums,
+    version: &Version,
+    transformer: &str,
+) -> io::Result<()> {
+    let path = manifest.stamp_path(version);
+    match stamp(manifest, checksums, version, transformer)? {
+        Some(stamp) => {
+            fs::create_dir_all(path.parent().expect("stamp paths have a parent"))?;
+            fs::write(path, format!("{}\n", stamp))
+        }
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use clap::ValueEnum;
+use openapiv3::{OpenAPI, PathItem, ReferenceOr};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::diagnostics::{Diagnostic, Diagnostics, Severity};
+use crate::paths;
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// How much of a document made it into its definition
+///
+/// Lists everything that was dropped, defaulted or guessed along the way.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    pub paths: usize,
+    pub operations: usize,
+    pub schemas: usize,
+    /// Number of findings of each kind, keyed by [`crate::TransformError::code`]
+    pub counts: BTreeMap<&'static str, usize>,
+    /// Sections left out on purpose, as OpenAPI can't express them
+    pub skipped: Vec<Finding>,
+    /// Sections, rows and cells left out of the definition
+    pub dropped: Vec<Finding>,
+    /// Values that were missing or invalid and replaced by a default
+    pub defaulted: Vec<Finding>,
+    /// Rules of OpenAPI the definition breaks
+    pub invalid: Vec<Finding>,
+    /// Types that weren't recognised and became a `$ref` to a schema of the same name
+    pub guessed: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub code: &'static str,
+    pub anchor: Option<String>,
+    pub heading: Option<String>,
+    pub message: String,
+}
+
+impl From<&Diagnostic> for Finding {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        Finding {
+            code: diagnostic.error.code(),
+            anchor: diagnostic.location.anchor.clone(),
+            heading: diagnostic.location.heading.clone(),
+            message: diagnostic.error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.anchor, &self.heading) {
+            (Some(anchor), _) => write!(f, "#{}: {}", anchor, self.message),
+            (None, Some(heading)) => write!(f, "{}: {}", heading, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// A schema name referenced by the definition
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reference {
+    pub schema: String,
+    /// How many times it is referenced
+    pub uses: usize,
+    /// Whether the definition has a schema of that name
+    pub resolved: bool,
+}
+
+impl Report {
+    pub fn new(spec: &OpenAPI, diagnostics: &Diagnostics) -> Self {
+        let mut report = Report {
+            paths: spec.paths.paths.len(),
+            operations: spec
+                .paths
+                .paths
+                .values()
+                .map(|path_item| match path_item {
+                    ReferenceOr::Item(path_item) => operation_count(path_item),
+                    ReferenceOr::Reference { .. } => 0,
+                })
+                .sum(),
+            schemas: spec
+                .components
+                .as_ref()
+                .map_or(0, |components| components.schemas.len()),
+            ..Default::default()
+        };
+
+        for diagnostic in diagnostics {
+            *report.counts.entry(diagnostic.error.code()).or_default() += 1;
+            match diagnostic.severity {
+                Severity::Info => report.skipped.push(diagnostic.into()),
+                Severity::Error => report.dropped.push(diagnostic.into()),
+                Severity::Warning => report.defaulted.push(diagnostic.into()),
+                Severity::Invalid => report.invalid.push(diagnostic.into()),
+            }
+        }
+
+        report.guessed = schema_refs(spec)
+            .into_iter()
+            .map(|(schema, uses)| Reference {
+                resolved: spec
+                    .components
+                    .iter()
+                    .any(|components| components.schemas.contains_key(&schema)),
+                schema,
+                uses,
+            })
+            .collect();
+
+        report
+    }
+
+    /// References to schemas the definition doesn't have
+    pub fn unresolved(&self) -> impl Iterator<Item = &Reference> {
+        self.guessed.iter().filter(|reference| !reference.resolved)
+    }
+}
+
+fn operation_count(path_item: &PathItem) -> usize {
+    paths::operations(path_item).count()
+}
+
+/// How many times each schema is referenced by the definition
+pub(crate) fn schema_refs(spec: &OpenAPI) -> BTreeMap<String, usize> {
+    let mut uses = BTreeMap::new();
+    count_schema_refs(
+        &serde_json::to_value(spec).expect("definitions serialize"),
+        &mut uses,
+    );
+    uses
+}
+
+fn count_schema_refs(value: &Value, uses: &mut BTreeMap<String, usize>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                if let Some(schema) = reference.strip_prefix(SCHEMA_REF_PREFIX) {
+                    *uses.entry(schema.to_string()).or_default() += 1;
+                }
+            }
+            object.values().for_each(|v| count_schema_refs(v, uses));
+        }
+        Value::Array(array) => array.iter().for_each(|v| count_schema_refs(v, uses)),
+        _ => {}
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} paths, {} operations, {} schemas",
+            self.paths, self.operations, self.schemas
+        )?;
+        writeln!(
+            f,
+            "{} skipped, {} dropped, {} defaulted, {} invalid, {} guessed references ({} unresolved)",
+            self.skipped.len(),
+            self.dropped.len(),
+            self.defaulted.len(),
+            self.invalid.len(),
+            self.guessed.len(),
+            self.unresolved().count()
+        )?;
+
+        if !self.counts.is_empty() {
+            writeln!(f, "\nBy kind:")?;
+            for (code, count) in self.counts.iter() {
+                writeln!(f, "  {:>5} {}", count, code)?;
+            }
+        }
+        if !self.skipped.is_empty() {
+            writeln!(f, "\nSkipped:")?;
+            for finding in self.skipped.iter() {
+                writeln!(f, "  {}", finding)?;
+            }
+        }
+        if !self.dropped.is_empty() {
+            writeln!(f, "\nDropped:")?;
+            for finding in self.dropped.iter() {
+                writeln!(f, "  {}", finding)?;
+            }
+        }
+        if !self.defaulted.is_empty() {
+            writeln!(f, "\nDefaulted:")?;
+            for finding in self.defaulted.iter() {
+                writeln!(f, "  {}", finding)?;
+            }
+        }
+        if !self.invalid.is_empty() {
+            writeln!(f, "\nInvalid:")?;
+            for finding in self.invalid.iter() {
+                writeln!(f, "  {}", finding)?;
+            }
+        }
+        if !self.guessed.is_empty() {
+            writeln!(f, "\nGuessed references:")?;
+            for reference in self.guessed.iter() {
+                writeln!(
+                    f,
+                    "  {:>5} {}{}",
+                    reference.uses,
+                    reference.schema,
+                    if reference.resolved {
+                        ""
+                    } else {
+                        " (unresolved)"
+                    }
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The serializations a report can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn render(self, report: &Report) -> serde_json::Result<String> {
+        match self {
+            Self::Text => Ok(report.to_string()),
+            Self::Json => Ok(format!("{}\n", serde_json::to_string_pretty(report)?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Report;
+    use crate::fixture::TRANSFORMED;
+
+    #[test]
+    fn accounts_for_the_documentation() {
+        let transformed = &*TRANSFORMED;
+        let report = Report::new(&transformed.spec, &transformed.diagnostics);
+
+        assert_eq!(report.paths, transformed.spec.paths.paths.len());
+        assert_eq!(
+            report.dropped.len() + report.defaulted.len() + report.invalid.len(),
+            transformed.diagnostics.len()
+        );
+        assert!(report
+            .dropped
+            .iter()
+            .any(|finding| finding.message.contains("/{any}")));
+        assert!(report
+            .guessed
+            .iter()
+            .any(|reference| reference.schema == "RealmRepresentation" && reference.resolved));
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::ir;
+
+lazy_static! {
+    static ref CURATED: RequiredOverrides =
+        serde_json::from_str(include_str!("../required-overrides.json")).unwrap();
+}
+
+/// Corrections to which properties a model's documentation marks required
+///
+/// Keyed by model name, such as
+/// `{"UserRepresentation": {"required_to_create": ["username"], "optional": ["id"]}}`.
+/// Models and properties a document doesn't have are ignored, so one list can
+/// serve every version.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct RequiredOverrides(BTreeMap<String, Override>);
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    /// Properties that are required whatever the documentation says
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Properties only required in the body of a `POST` creating the model.
+    /// The model stays as it is, as it is also sent as a partial update
+    #[serde(default)]
+    pub required_to_create: Vec<String>,
+    /// Properties that are optional whatever the documentation says
+    #[serde(default)]
+    pub optional: Vec<String>,
+}
+
+impl RequiredOverrides {
+    /// The list kept alongside the transformer, for representations whose documentation is wrong
+    pub fn curated() -> Self {
+        CURATED.clone()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn apply(&self, api: &mut ir::Api) {
+        for model in api.models.iter_mut() {
+            let corrections = match self.0.get(&model.name) {
+                Some(corrections) => corrections,
+                None => continue,
+            };
+            for property in model.properties.iter_mut() {
+                if corrections.required.contains(&property.name) {
+                    property.required = true;
+                } else if corrections.optional.contains(&property.name) {
+                    property.required = false;
+                }
+            }
+        }
+
+        let models = &api.models;
+        let bodies = api
+            .resources
+            .iter_mut()
+            .flat_map(|resource| resource.endpoints.iter_mut())
+            .filter(|endpoint| endpoint.verb == "POST")
+            .filter_map(|endpoint| endpoint.body.as_mut());
+        for body in bodies {
+            let model = match models.iter().find(|model| model.name == body.raw_type) {
+                Some(model) => model,
+                None => continue,
+            };
+            if let Some(corrections) = self.0.get(&model.name) {
+                body.required = corrections
+                    .required_to_create
+                    .iter()
+                    .filter(|name| model.properties.iter().any(|p| &p.name == *name))
+                    .cloned()
+                    .collect();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RequiredOverrides;
+    use crate::ir::{Api, Body, Endpoint, Model, Property, Resource};
+    use serde_json::json;
+
+    fn property(name: &str, required: bool) -> Property {
+        Property {
+            name: name.into(),
+            raw_type: "String".into(),
+            required,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn corrects_the_documentation() {
+        let overrides: RequiredOverrides = serde_json::from_str(
+            r#"{"UserRepresentation": {"required": ["username"], "optional": ["id", "email"]}}"#,
+        )
+        .unwrap();
+        let mut api = Api {
+            models: vec![Model {
+                name: "UserRepresentation".into(),
+                properties: vec![property("id", true), property("username", false)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        overrides.apply(&mut api);
+
+        assert_eq!(
+            api.models[0].properties,
+            vec![property("id", false), property("username", true)]
+        );
+        assert!(RequiredOverrides::curated()
+            .0
+            .contains_key("UserRepresentation"));
+    }
+
+    #[test]
+    fn requires_properties_to_create_only_in_post_bodies() {
+        let overrides: RequiredOverrides = serde_json::from_str(
+            r#"{"UserRepresentation": {"required_to_create": ["username", "missing"]}}"#,
+        )
+        .unwrap();
+        let endpoint = |verb: &str, path: &str| Endpoint {
+            verb: verb.into(),
+            path: path.into(),
+            body: Some(Body {
+                raw_type: "UserRepresentation".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut api = Api {
+            resources: vec![Resource {
+                tag: "Users".into(),
+                endpoints: vec![
+                    endpoint("POST", "/{realm}/users"),
+                    endpoint("PUT", "/{realm}/users/{id}"),
+                ],
+            }],
+            models: vec![Model {
+                name: "UserRepresentation".into(),
+                properties: vec![property("id", false), property("username", false)],
+                ..Default::default()
+            }],
+        };
+        overrides.apply(&mut api);
+
+        assert_eq!(
+            api.models[0].properties,
+            vec![property("id", false), property("username", false)]
+        );
+        let (paths, _) = crate::paths::paths(&api);
+        let paths = serde_json::to_value(&paths).unwrap();
+        assert_eq!(
+            paths["/{realm}/users"]["post"]["requestBody"]["content"]["application/json"]["schema"],
+            json!({
+                "allOf": [
+                    { "$ref": "#/components/schemas/UserRepresentation" },
+                    { "type": "object", "required": ["username"] }
+                ]
+            })
+        );
+        assert_eq!(
+            paths["/{realm}/users/{id}"]["put"]["requestBody"]["content"]["application/json"]
+                ["schema"],
+            json!({ "$ref": "#/components/schemas/UserRepresentation" })
+        );
+    }
+}
@@ -0,0 +1,653 @@
+use indexmap::IndexMap;
+use regex::Regex;
+use scraper::element_ref::ElementRef;
+use scraper::Selector;
+
+use crate::diagnostics::{Diagnostics, Location};
+use crate::error::TransformError;
+use crate::index::{self, Index};
+use crate::ir;
+use crate::layout::Layout;
+use crate::markdown;
+use crate::paths::verb_path::VerbPath;
+use crate::table::{cell, column_index, parse_table_rows};
+
+lazy_static! {
+    static ref PATH_PARAM_REGEX: Regex = Regex::new(r"\{([^}]+)}").unwrap();
+    static ref TABLE_SELECTOR: Selector = Selector::parse("table").unwrap();
+}
+
+/// Read the API a document describes from its sections
+///
+/// Anything missing from a section, such as a parameter's name or a
+/// property's type, is reported here so emitters only see what can be used.
+pub fn scrape(index: &Index<'_>, diagnostics: &mut Diagnostics) -> ir::Api {
+    let layout = index.layout;
+
+    let resources = index
+        .resources
+        .iter()
+        .map(|resource| ir::Resource {
+            tag: resource.tag.clone(),
+            endpoints: resource
+                .endpoints
+                .iter()
+                .map(|endpoint| scrape_endpoint(endpoint, layout, diagnostics))
+                .collect(),
+        })
+        .collect();
+
+    let models = index
+        .models
+        .iter()
+        .map(|model| scrape_model(model, layout, diagnostics))
+        .collect();
+
+    ir::Api { resources, models }
+}
+
+fn scrape_endpoint(
+    endpoint: &index::Endpoint<'_>,
+    layout: &Layout,
+    diagnostics: &mut Diagnostics,
+) -> ir::Endpoint {
+    let section = &endpoint.section;
+
+    let response = match response(section, layout) {
+        Ok(response) => Some(response),
+        Err(error) => {
+            diagnostics.warn(Location::of(section), error);
+            None
+        }
+    };
+
+    ir::Endpoint {
+        verb: endpoint.verb_path.verb.clone(),
+        path: endpoint.path.clone(),
+        position: endpoint.position,
+        anchor: Location::of(section).anchor,
+        description: apply_selector(section, &layout.description),
+        status_code: status_code(section, layout, diagnostics),
+        response,
+        query_params: params(
+            section,
+            &layout.query_params_table,
+            layout,
+            "Query",
+            diagnostics,
+        ),
+        body: body(section, layout, diagnostics),
+        path_params: path_params(section, layout, &endpoint.verb_path, diagnostics),
+    }
+}
+
+fn apply_selector(section: &ElementRef<'_>, selector: &Selector) -> Option<String> {
+    section
+        .select(selector)
+        .next()
+        .map(|s| s.text().collect::<String>().trim().into())
+}
+
+fn parse_status_code(status_code_str: &str) -> Option<u16> {
+    let status_code_u16 = status_code_str.parse().ok()?;
+
+    if !(100..=599).contains(&status_code_u16) {
+        return None;
+    }
+
+    Some(status_code_u16)
+}
+
+fn status_code(
+    section: &ElementRef<'_>,
+    layout: &Layout,
+    diagnostics: &mut Diagnostics,
+) -> Option<u16> {
+    let code = apply_selector(section, &layout.status_code);
+
+    match code.as_deref().and_then(parse_status_code) {
+        Some(status_code) => Some(status_code),
+        None => {
+            diagnostics.warn(
+                Location::of(section),
+                TransformError::DefaultedStatusCode(code),
+            );
+            None
+        }
+    }
+}
+
+/// The successful response described by an endpoint's responses table
+pub fn response(section: &ElementRef<'_>, layout: &Layout) -> Result<ir::Response, TransformError> {
+    let response_table = section
+        .select(&layout.responses)
+        .next()
+        .ok_or(TransformError::MissingResponseTable)?;
+    let description = response_table
+        .select(&layout.response_description)
+        .next()
+        .ok_or(TransformError::MissingResponseCell("description"))?
+        .text()
+        .collect();
+    let raw_type = response_table
+        .select(&layout.response_schema)
+        .next()
+        .ok_or(TransformError::MissingResponseCell("schema"))?
+        .text()
+        .collect();
+    let media_type = section
+        .select(&layout.produces)
+        .next()
+        .map(|p| p.text().collect::<String>());
+
+    Ok(ir::Response {
+        description,
+        media_type,
+        raw_type,
+    })
+}
+
+/// The rows of a parameters table describing parameters of one kind
+fn parameter_rows(
+    section: &ElementRef<'_>,
+    table: &Selector,
+    layout: &Layout,
+    kind: &str,
+) -> Vec<IndexMap<String, String>> {
+    let mut rows = parse_table_rows(section, table);
+    if let Some(column) = layout.parameter_kind_column {
+        rows.retain(|row| matches!(row.get(column), Some(k) if k.trim() == kind));
+    }
+    rows
+}
+
+/// The parameters of one kind, reporting rows without a name
+fn params(
+    section: &ElementRef<'_>,
+    table: &Selector,
+    layout: &Layout,
+    kind: &str,
+    diagnostics: &mut Diagnostics,
+) -> Vec<ir::Param> {
+    parameter_rows(section, table, layout, kind)
+        .into_iter()
+        .filter_map(
+            |row| match row.get("Name").and_then(|name| name.split('\n').next()) {
+                Some(name) if !name.is_empty() => Some(ir::Param {
+                    name: name.to_string(),
+                    description: row
+                        .get("Description")
+                        .filter(|description| !description.is_empty())
+                        .cloned(),
+                }),
+                _ => {
+                    diagnostics.error(Location::of(section), TransformError::MissingParameterName);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// The path parameters, renamed to match a path with numbered ids and in the path's order
+fn path_params(
+    section: &ElementRef<'_>,
+    layout: &Layout,
+    verb_path: &VerbPath,
+    diagnostics: &mut Diagnostics,
+) -> Vec<ir::Param> {
+    let mut params = params(
+        section,
+        &layout.path_params_table,
+        layout,
+        "Path",
+        diagnostics,
+    );
+
+    if let Some(repeats) = verb_path.repeating_ids() {
+        params.retain(|param| param.name != "id");
+        params.extend((1..=repeats).map(|i| ir::Param {
+            name: format!("id{}", i),
+            description: None,
+        }));
+    }
+
+    for (index, cap) in PATH_PARAM_REGEX
+        .captures_iter(&verb_path.path())
+        .enumerate()
+        .take(params.len())
+    {
+        if let Some(position) = params.iter().position(|param| param.name == cap[1]) {
+            params.swap(index, position);
+        }
+    }
+
+    params
+}
+
+fn body(
+    section: &ElementRef<'_>,
+    layout: &Layout,
+    diagnostics: &mut Diagnostics,
+) -> Option<ir::Body> {
+    let row = parameter_rows(section, &layout.body_param_table, layout, "Body")
+        .into_iter()
+        .next()?;
+    match row.get(layout.body_type_column) {
+        Some(raw_type) => Some(ir::Body {
+            raw_type: raw_type.clone(),
+            ..Default::default()
+        }),
+        None => {
+            diagnostics.error(
+                Location::of(section),
+                TransformError::NoFindErr(format!(
+                    "body parameter {} column",
+                    layout.body_type_column
+                )),
+            );
+            None
+        }
+    }
+}
+
+fn scrape_model(
+    model: &index::Model<'_>,
+    layout: &Layout,
+    diagnostics: &mut Diagnostics,
+) -> ir::Model {
+    let section = &model.section;
+    let description_column = section
+        .select(&TABLE_SELECTOR)
+        .next()
+        .and_then(|table| column_index(&table, layout.property_description_column));
+    let required_column = section
+        .select(&TABLE_SELECTOR)
+        .next()
+        .and_then(|table| column_index(&table, layout.property_required_column));
+    let format_column = section
+        .select(&TABLE_SELECTOR)
+        .next()
+        .and_then(|table| column_index(&table, layout.property_format_column));
+    let properties = section
+        .select(&layout.property_rows)
+        .filter_map(|row| {
+            let name = match row.select(&layout.property_name).next() {
+                Some(name) => name.text().collect::<String>(),
+                None => {
+                    diagnostics.error(Location::of(section), TransformError::MissingPropertyName);
+                    return None;
+                }
+            };
+            match row.select(&layout.property_type).next() {
+                Some(raw_type) => Some(ir::Property {
+                    name,
+                    raw_type: raw_type.text().collect(),
+                    description: description(
+                        description_column.and_then(|column| cell(&row, column)),
+                    ),
+                    required: required(&row, required_column, layout),
+                    values: format_column
+                        .and_then(|column| cell(&row, column))
+                        .and_then(|cell| listed_values(&cell.text().collect::<String>()))
+                        .unwrap_or_default(),
+                }),
+                None => {
+                    diagnostics.error(
+                        Location::of(section),
+                        TransformError::MissingPropertyType(name),
+                    );
+                    None
+                }
+            }
+        })
+        .collect();
+
+    ir::Model {
+        name: model.name.clone(),
+        anchor: Location::of(section).anchor,
+        description: description(section.select(&layout.schema_description)),
+        properties,
+    }
+}
+
+/// Whether a property row marks the property required, with a mark such as
+/// `X` or `true` in a required column or `required` beneath its name
+///
+/// Only explicit marks count, so a column holding something else, such as
+/// `-`, `no` or `nullable`, doesn't make every property required.
+fn required(row: &ElementRef<'_>, column: Option<usize>, layout: &Layout) -> bool {
+    let marker = column
+        .and_then(|column| cell(row, column))
+        .map(|cell| cell.text().collect::<String>().trim().to_lowercase())
+        .unwrap_or_default();
+    let marked = matches!(
+        marker.as_str(),
+        "x" | "\u{2713}" | "\u{2714}" | "true" | "yes" | "required"
+    );
+    marked
+        || row
+            .select(&layout.property_optionality)
+            .any(|marker| marker.text().collect::<String>().trim() == "required")
+}
+
+/// The values listed in a format cell, such as `Enum: POSITIVE, NEGATIVE,`
+fn listed_values(format: &str) -> Option<Vec<String>> {
+    let (_, values) = format.split_once("Enum:")?;
+    let values: Vec<String> = values
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+/// The prose of some elements as one CommonMark description, if there is any
+fn description<'a>(elements: impl IntoIterator<Item = ElementRef<'a>>) -> Option<String> {
+    let paragraphs: Vec<String> = elements
+        .into_iter()
+        .map(|element| markdown::from_html(&element))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect();
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scrape;
+    use crate::diagnostics::Diagnostics;
+    use crate::error::TransformError;
+    use crate::index::Index;
+    use crate::ir;
+    use crate::layout::Layout;
+    use scraper::Html;
+
+    const HTML: &str = r#"
+        <h2 id="_resources">Resources</h2>
+        <div class="sectionbody">
+            <div class="sect2">
+                <h3>Users</h3>
+                <div class="sect3">
+                    <h4 id="_users_groups">PUT /admin/realms/{realm}/users/{id}/groups/{id}</h4>
+                    <div class="paragraph"><p>Join a group</p></div>
+                    <h6 id="_path_parameters">Path Parameters</h6>
+                    <table>
+                        <thead><tr><th>Name</th><th>Description</th></tr></thead>
+                        <tbody>
+                            <tr><td>realm</td><td>realm name (not id!)</td></tr>
+                            <tr><td>id</td><td></td></tr>
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+        <h2 id="models">Models</h2>
+        <div class="sectionbody">
+            <div class="sect2">
+                <h3 id="UserRepresentation">UserRepresentation</h3>
+                <div class="paragraph"><p>A user of a <em>realm</em></p></div>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>Name</th><th>Type</th><th>Description</th><th>Required</th>
+                            <th>Format</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td><p><strong>username</strong></p></td>
+                            <td><p>String</p></td>
+                            <td><p>Unique within the realm, see <code>email</code></p></td>
+                            <td><p>X</p></td>
+                            <td></td>
+                        </tr>
+                        <tr>
+                            <td><p><strong>logic</strong></p></td>
+                            <td><p>Logic</p></td>
+                            <td></td>
+                            <td></td>
+                            <td><p><em>Enum:</em> POSITIVE, NEGATIVE,</p></td>
+                        </tr>
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    "#;
+
+    #[test]
+    fn keeps_what_the_documentation_says() {
+        let document = Html::parse_document(HTML);
+        let mut diagnostics = Diagnostics::default();
+        let index = Index::new(&document, Layout::current(), &mut diagnostics);
+        let api = scrape(&index, &mut diagnostics);
+
+        let endpoint = api.endpoints().next().unwrap();
+        assert_eq!(endpoint.verb, "PUT");
+        assert_eq!(endpoint.path, "/{realm}/users/{id1}/groups/{id2}");
+        assert_eq!(endpoint.anchor.as_deref(), Some("_users_groups"));
+        assert_eq!(endpoint.description.as_deref(), Some("Join a group"));
+        assert_eq!(
+            endpoint
+                .path_params
+                .iter()
+                .map(|param| param.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["realm", "id1", "id2"]
+        );
+        assert_eq!(endpoint.response, None);
+        assert_eq!(endpoint.status_code, None);
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.error == TransformError::MissingResponseTable));
+
+        assert_eq!(
+            api.models,
+            vec![ir::Model {
+                name: "UserRepresentation".into(),
+                anchor: Some("UserRepresentation".into()),
+                description: Some("A user of a _realm_".into()),
+                properties: vec![
+                    ir::Property {
+                        name: "username".into(),
+                        raw_type: "String".into(),
+                        description: Some("Unique within the realm, see `email`".into()),
+                        required: true,
+                        values: Vec::new(),
+                    },
+                    ir::Property {
+                        name: "logic".into(),
+                        raw_type: "Logic".into(),
+                        description: None,
+                        required: false,
+                        values: vec!["POSITIVE".into(), "NEGATIVE".into()],
+                    }
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn only_explicit_marks_make_properties_required() {
+        let rows: String = [
+            ("x", "X"),
+            ("tick", "\u{2713}"),
+            ("truthy", "true"),
+            ("blank", ""),
+            ("dash", "-"),
+            ("no", "No"),
+            ("nullable", "nullable"),
+        ]
+        .iter()
+        .map(|(name, marker)| {
+            format!(
+                "<tr><td><p><strong>{}</strong></p></td><td><p>String</p></td><td><p>{}</p></td></tr>",
+                name, marker
+            )
+        })
+        .collect();
+        let html = format!(
+            r#"
+            <h2 id="models">Models</h2>
+            <div class="sectionbody">
+                <div class="sect2">
+                    <h3>ClientRepresentation</h3>
+                    <table>
+                        <thead><tr><th>Name</th><th>Type</th><th>required</th></tr></thead>
+                        <tbody>{}</tbody>
+                    </table>
+                </div>
+            </div>
+            "#,
+            rows
+        );
+        let document = Html::parse_document(&html);
+        let mut diagnostics = Diagnostics::default();
+        let index = Index::new(&document, Layout::current(), &mut diagnostics);
+        let api = scrape(&index, &mut diagnostics);
+
+        assert_eq!(
+            api.models[0]
+                .properties
+                .iter()
+                .filter(|property| property.required)
+                .map(|property| property.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["x", "tick", "truthy"]
+        );
+    }
+
+    /// As swagger2markup lays out the documentation of Keycloak 5.0 to 12.0
+    const SWAGGER2MARKUP_HTML: &str = r##"
+        <h2 id="_resources">Resources</h2>
+        <div class="sectionbody">
+            <div class="sect2">
+                <h3 id="_users_resource">Users</h3>
+                <div class="sect3">
+                    <h4 id="_updateuser">Update the user</h4>
+                    <div class="literalblock">
+                        <div class="content"><pre>PUT /{realm}/users/{id}</pre></div>
+                    </div>
+                    <div class="sect4">
+                        <h5 id="_parameters_47">Parameters</h5>
+                        <table class="tableblock frame-all grid-all spread">
+                            <thead>
+                                <tr><th>Type</th><th>Name</th><th>Description</th><th>Schema</th></tr>
+                            </thead>
+                            <tbody>
+                                <tr>
+                                    <td><p class="tableblock"><strong>Path</strong></p></td>
+                                    <td><p class="tableblock"><strong>id</strong><br>
+<em>required</em></p></td>
+                                    <td><p class="tableblock">User id</p></td>
+                                    <td><p class="tableblock">string</p></td>
+                                </tr>
+                                <tr>
+                                    <td><p class="tableblock"><strong>Path</strong></p></td>
+                                    <td><p class="tableblock"><strong>realm</strong><br>
+<em>required</em></p></td>
+                                    <td><p class="tableblock">realm name (not id!)</p></td>
+                                    <td><p class="tableblock">string</p></td>
+                                </tr>
+                                <tr>
+                                    <td><p class="tableblock"><strong>Query</strong></p></td>
+                                    <td><p class="tableblock"><strong>briefRepresentation</strong><br>
+<em>optional</em></p></td>
+                                    <td></td>
+                                    <td><p class="tableblock">boolean</p></td>
+                                </tr>
+                                <tr>
+                                    <td><p class="tableblock"><strong>Body</strong></p></td>
+                                    <td><p class="tableblock"><strong>rep</strong><br>
+<em>required</em></p></td>
+                                    <td><p class="tableblock">UserRepresentation</p></td>
+                                    <td><p class="tableblock"><a href="#_userrepresentation">UserRepresentation</a></p></td>
+                                </tr>
+                            </tbody>
+                        </table>
+                    </div>
+                    <div class="sect4">
+                        <h5 id="_responses_47">Responses</h5>
+                        <table class="tableblock frame-all grid-all spread">
+                            <thead><tr><th>HTTP Code</th><th>Description</th><th>Schema</th></tr></thead>
+                            <tbody>
+                                <tr>
+                                    <td><p class="tableblock"><strong>2XX</strong></p></td>
+                                    <td><p class="tableblock">success</p></td>
+                                    <td><p class="tableblock">No Content</p></td>
+                                </tr>
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+            </div>
+        </div>
+        <h2 id="_definitions">Definitions</h2>
+        <div class="sectionbody">
+            <div class="sect2">
+                <h3 id="_userrepresentation">UserRepresentation</h3>
+                <table class="tableblock frame-all grid-all spread">
+                    <thead><tr><th>Name</th><th>Schema</th></tr></thead>
+                    <tbody>
+                        <tr>
+                            <td><p class="tableblock"><strong>groups</strong><br>
+<em>optional</em></p></td>
+                            <td><p class="tableblock">&lt; string &gt; array</p></td>
+                        </tr>
+                        <tr>
+                            <td><p class="tableblock"><strong>username</strong><br>
+<em>required</em></p></td>
+                            <td><p class="tableblock">string</p></td>
+                        </tr>
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    "##;
+
+    #[test]
+    fn reads_the_swagger2markup_layout() {
+        let document = Html::parse_document(SWAGGER2MARKUP_HTML);
+        let layout = Layout::detect(&document);
+        assert_eq!(layout.name, "swagger2markup");
+        let mut diagnostics = Diagnostics::default();
+        let index = Index::new(&document, layout, &mut diagnostics);
+        let api = scrape(&index, &mut diagnostics);
+
+        let endpoint = api.endpoints().next().unwrap();
+        assert_eq!(endpoint.verb, "PUT");
+        assert_eq!(endpoint.path, "/{realm}/users/{id}");
+        let names =
+            |params: &[ir::Param]| params.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&endpoint.path_params), vec!["realm", "id"]);
+        assert_eq!(names(&endpoint.query_params), vec!["briefRepresentation"]);
+        assert_eq!(
+            endpoint.body,
+            Some(ir::Body {
+                raw_type: "UserRepresentation".into(),
+                ..Default::default()
+            })
+        );
+
+        let properties = &api.models[0].properties;
+        assert_eq!(api.models[0].name, "UserRepresentation");
+        assert_eq!(
+            properties
+                .iter()
+                .map(|p| (p.name.as_str(), p.raw_type.as_str(), p.required))
+                .collect::<Vec<_>>(),
+            vec![
+                ("groups", "< string > array", false),
+                ("username", "string", true)
+            ]
+        );
+    }
+}
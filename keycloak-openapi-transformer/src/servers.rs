@@ -0,0 +1,137 @@
+use indexmap::IndexMap;
+use openapiv3::{Server, ServerVariable};
+
+/// Keycloak 17 was the first release to default to the Quarkus distribution,
+/// which serves everything from the root instead of under `/auth`.
+const FIRST_QUARKUS_MAJOR_VERSION: u32 = 17;
+
+#[derive(Debug, Default, Clone)]
+pub struct ServerOptions {
+    /// Literal server URLs. When given they replace the templated server
+    pub urls: Vec<String>,
+    /// Default of the `contextPath` variable, overriding the version's default
+    pub context_path: Option<String>,
+}
+
+/// The context path Keycloak is served from by default
+///
+/// WildFly-era releases live under `/auth` while Quarkus releases live at the
+/// root. Unknown versions are assumed to be recent.
+pub fn default_context_path(keycloak_version: Option<&str>) -> &'static str {
+    let major = keycloak_version
+        .and_then(|version| version.split('.').next())
+        .and_then(|major| major.parse::<u32>().ok());
+
+    match major {
+        Some(major) if major < FIRST_QUARKUS_MAJOR_VERSION => "/auth",
+        _ => "",
+    }
+}
+
+fn variable(default: &str, enumeration: &[&str], description: &str) -> ServerVariable {
+    ServerVariable {
+        enumeration: enumeration.iter().map(|e| e.to_string()).collect(),
+        default: default.to_string(),
+        description: Some(description.to_string()),
+        extensions: Default::default(),
+    }
+}
+
+pub fn servers(keycloak_version: Option<&str>, options: &ServerOptions) -> Vec<Server> {
+    if !options.urls.is_empty() {
+        return options
+            .urls
+            .iter()
+            .map(|url| Server {
+                url: url.clone(),
+                ..Default::default()
+            })
+            .collect();
+    }
+
+    let context_path = options
+        .context_path
+        .as_deref()
+        .unwrap_or_else(|| default_context_path(keycloak_version))
+        .trim_end_matches('/');
+
+    let mut variables = IndexMap::new();
+    variables.insert(
+        "scheme".to_string(),
+        variable(
+            "https",
+            &["http", "https"],
+            "The protocol Keycloak is served over",
+        ),
+    );
+    variables.insert(
+        "host".to_string(),
+        variable(
+            "keycloak.example.com",
+            &[],
+            "The host name of the Keycloak server",
+        ),
+    );
+    variables.insert(
+        "port".to_string(),
+        variable("443", &[], "The port Keycloak listens on"),
+    );
+    variables.insert(
+        "contextPath".to_string(),
+        variable(
+            context_path,
+            &[],
+            "The path Keycloak is served from, `/auth` for releases before Keycloak 17",
+        ),
+    );
+
+    vec![Server {
+        url: "{scheme}://{host}:{port}{contextPath}/admin/realms".into(),
+        description: None,
+        variables: Some(variables),
+        extensions: Default::default(),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_context_path, servers, ServerOptions};
+
+    #[test]
+    fn wildfly_releases_are_served_under_auth() {
+        assert_eq!(default_context_path(Some("16.1")), "/auth");
+        assert_eq!(default_context_path(Some("12.0-patched")), "/auth");
+    }
+
+    #[test]
+    fn quarkus_releases_are_served_from_the_root() {
+        assert_eq!(default_context_path(Some("17.0")), "");
+        assert_eq!(default_context_path(Some("23.0.7")), "");
+        assert_eq!(default_context_path(None), "");
+    }
+
+    #[test]
+    fn context_path_can_be_overridden() {
+        let options = ServerOptions {
+            context_path: Some("/sso/".into()),
+            ..Default::default()
+        };
+        let servers = servers(Some("23.0.7"), &options);
+        let variables = servers[0].variables.as_ref().unwrap();
+
+        assert_eq!(variables["contextPath"].default, "/sso");
+    }
+
+    #[test]
+    fn literal_urls_replace_the_template() {
+        let options = ServerOptions {
+            urls: vec!["http://localhost:8080/auth/admin/realms".into()],
+            ..Default::default()
+        };
+        let servers = servers(Some("23.0.7"), &options);
+
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].url, "http://localhost:8080/auth/admin/realms");
+        assert_eq!(servers[0].variables, None);
+    }
+}
@@ -1,5 +1,4 @@
-use std::collections::HashMap;
-
+use indexmap::IndexMap;
 use scraper::Selector;
 
 lazy_static! {
@@ -8,10 +7,31 @@ lazy_static! {
     static ref CELL_SELECTOR: Selector = Selector::parse("td").unwrap();
 }
 
+/// Where the column with a heading, in any case, is among each row's cells
+pub fn column_index(table: &scraper::element_ref::ElementRef<'_>, heading: &str) -> Option<usize> {
+    table.select(&HEADINGS_SELECTOR).position(|th| {
+        th.text()
+            .collect::<String>()
+            .trim()
+            .eq_ignore_ascii_case(heading)
+    })
+}
+
+/// The cell of a row in the column at `index`
+pub fn cell<'a>(
+    row: &scraper::element_ref::ElementRef<'a>,
+    index: usize,
+) -> Option<scraper::element_ref::ElementRef<'a>> {
+    row.children()
+        .filter_map(scraper::element_ref::ElementRef::wrap)
+        .filter(|child| child.value().name() == "td")
+        .nth(index)
+}
+
 pub fn parse_table_rows<'a>(
     container: &'a scraper::element_ref::ElementRef<'a>,
     selector: &'a Selector,
-) -> Vec<HashMap<String, String>> {
+) -> Vec<IndexMap<String, String>> {
     let table = container.select(selector).next();
     if let Some(table) = table {
         let headings = table
@@ -25,7 +45,7 @@ pub fn parse_table_rows<'a>(
                     .map(|td| td.text().collect::<String>())
                     .zip(headings.iter())
                     .map(|(value, heading)| (heading.clone(), value))
-                    .collect::<HashMap<String, String>>()
+                    .collect::<IndexMap<String, String>>()
             })
             .collect()
     } else {
@@ -0,0 +1,318 @@
+use openapiv3::{
+    IntegerFormat, OpenAPI, Parameter, ParameterSchemaOrContent, ReferenceOr, Schema, SchemaKind,
+    StringFormat, Type, VariantOrUnknownOrEmpty,
+};
+
+use crate::formats::Match;
+use crate::paths;
+
+/// Extension giving the unit of a number, such as [`EPOCH_MILLIS`]
+pub const UNIT_EXTENSION: &str = "x-unit";
+
+/// Milliseconds since the Unix epoch
+pub const EPOCH_MILLIS: &str = "epoch-millis";
+
+/// Describes an [`EPOCH_MILLIS`] timestamp the documentation doesn't describe
+const EPOCH_MILLIS_DESCRIPTION: &str = "Milliseconds since the Unix epoch.";
+
+/// The names Keycloak gives `long` epoch-millisecond timestamps, and the
+/// `yyyy-MM-dd` date parameters of its event queries
+///
+/// `notBefore` and `expiration` are left out: they are `int` seconds.
+pub fn default_rules() -> Vec<Match> {
+    vec![
+        Match::Suffix("Timestamp".into()),
+        Match::Name("time".into()),
+        Match::Name("start".into()),
+        Match::Name("lastAccess".into()),
+        Match::Name("dateFrom".into()),
+        Match::Name("dateTo".into()),
+        Match::Hint("since the epoch".into()),
+    ]
+}
+
+/// Mark the definition's properties and parameters that match a rule as timestamps
+///
+/// `int64` ones are epoch-millisecond timestamps and get a [`UNIT_EXTENSION`]
+/// of [`EPOCH_MILLIS`], letting code generators map them to a date type, and
+/// a description saying so unless they have one.
+/// Strings without a format or listed values are dates, and get the `date`
+/// format. Anything else, such as an `int32`, is left alone. Arrays of
+/// timestamps have their items marked, as do the properties of inline request
+/// and response bodies.
+pub fn mark(spec: &mut OpenAPI, rules: &[Match]) {
+    if rules.is_empty() {
+        return;
+    }
+
+    if let Some(components) = &mut spec.components {
+        for schema in components.schemas.values_mut() {
+            if let ReferenceOr::Item(schema) = schema {
+                mark_properties(schema, rules);
+            }
+        }
+    }
+
+    for path_item in spec.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path_item) = path_item {
+            for parameter in path_item.parameters.iter_mut() {
+                mark_parameter(parameter, rules);
+            }
+            for operation in paths::operations_mut(path_item) {
+                for parameter in operation.parameters.iter_mut() {
+                    mark_parameter(parameter, rules);
+                }
+                let responses = operation
+                    .responses
+                    .default
+                    .iter_mut()
+                    .chain(operation.responses.responses.values_mut())
+                    .filter_map(|response| match response {
+                        ReferenceOr::Item(response) => Some(&mut response.content),
+                        ReferenceOr::Reference { .. } => None,
+                    });
+                let bodies = operation
+                    .request_body
+                    .iter_mut()
+                    .filter_map(|body| match body {
+                        ReferenceOr::Item(body) => Some(&mut body.content),
+                        ReferenceOr::Reference { .. } => None,
+                    })
+                    .chain(responses);
+                for media_type in bodies.flat_map(|content| content.values_mut()) {
+                    if let Some(ReferenceOr::Item(schema)) = &mut media_type.schema {
+                        mark_properties(schema, rules);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn mark_properties(schema: &mut Schema, rules: &[Match]) {
+    if let SchemaKind::Type(Type::Object(object)) = &mut schema.schema_kind {
+        for (name, property) in object.properties.iter_mut() {
+            if let ReferenceOr::Item(property) = property {
+                if rules
+                    .iter()
+                    .any(|rule| rule.matches(name, property.schema_data.description.as_deref()))
+                {
+                    mark_schema(property);
+                }
+                mark_properties(property, rules);
+            }
+        }
+    }
+}
+
+fn mark_parameter(parameter: &mut ReferenceOr<Parameter>, rules: &[Match]) {
+    let parameter_data = match parameter {
+        ReferenceOr::Item(Parameter::Path { parameter_data, .. })
+        | ReferenceOr::Item(Parameter::Query { parameter_data, .. })
+        | ReferenceOr::Item(Parameter::Header { parameter_data, .. })
+        | ReferenceOr::Item(Parameter::Cookie { parameter_data, .. }) => parameter_data,
+        ReferenceOr::Reference { .. } => return,
+    };
+    let matched = rules
+        .iter()
+        .any(|rule| rule.matches(&parameter_data.name, parameter_data.description.as_deref()));
+    if let ParameterSchemaOrContent::Schema(ReferenceOr::Item(schema)) = &mut parameter_data.format
+    {
+        if matched {
+            mark_schema(schema);
+        }
+    }
+}
+
+fn mark_schema(schema: &mut Schema) {
+    match &mut schema.schema_kind {
+        SchemaKind::Type(Type::Integer(integer))
+            if integer.format == VariantOrUnknownOrEmpty::Item(IntegerFormat::Int64) =>
+        {
+            schema
+                .schema_data
+                .extensions
+                .entry(UNIT_EXTENSION.to_string())
+                .or_insert_with(|| EPOCH_MILLIS.into());
+            schema
+                .schema_data
+                .description
+                .get_or_insert_with(|| EPOCH_MILLIS_DESCRIPTION.to_string());
+        }
+        SchemaKind::Type(Type::String(string))
+            if string.format == VariantOrUnknownOrEmpty::Empty && string.enumeration.is_empty() =>
+        {
+            string.format = VariantOrUnknownOrEmpty::Item(StringFormat::Date);
+        }
+        SchemaKind::Type(Type::Array(array)) => {
+            if let Some(ReferenceOr::Item(items)) = &mut array.items {
+                mark_schema(items);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_rules, mark};
+    use crate::fixture::HTML;
+    use crate::{transform, Options};
+    use openapiv3::OpenAPI;
+    use serde_json::json;
+
+    #[test]
+    fn marks_timestamps() {
+        let mut spec: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.2",
+            "info": { "title": "Keycloak Admin REST API", "version": "1" },
+            "paths": {
+                "/{realm}/events": {
+                    "get": {
+                        "parameters": [
+                            { "in": "query", "name": "dateFrom", "schema": { "type": "string" } },
+                            {
+                                "in": "query",
+                                "name": "dateTo",
+                                "schema": { "type": "string", "format": "date-time" }
+                            },
+                            { "in": "query", "name": "first", "schema": { "type": "integer", "format": "int64" } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "success",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": {
+                                                "time": { "type": "integer", "format": "int64" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "createdTimestamp": { "type": "integer", "format": "int64" }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        "responses": {}
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "UserRepresentation": {
+                        "type": "object",
+                        "properties": {
+                            "createdTimestamp": { "type": "integer", "format": "int64" },
+                            "notBefore": { "type": "integer", "format": "int32" },
+                            "expiration": { "type": "integer", "format": "int64" },
+                            "lastAccess": {
+                                "type": "integer",
+                                "format": "int64",
+                                "description": "When the user last logged in"
+                            },
+                            "modifiedTimestamp": {
+                                "type": "integer",
+                                "format": "int32"
+                            },
+                            "time": { "type": "string", "enum": ["now"] },
+                            "username": { "type": "string" }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        mark(&mut spec, &default_rules());
+        // Marking twice changes nothing, so normalised definitions can be marked again
+        mark(&mut spec, &default_rules());
+
+        let spec = serde_json::to_value(spec).unwrap();
+        assert_eq!(
+            spec["components"]["schemas"]["UserRepresentation"]["properties"],
+            json!({
+                "createdTimestamp": {
+                    "type": "integer",
+                    "format": "int64",
+                    "description": "Milliseconds since the Unix epoch.",
+                    "x-unit": "epoch-millis"
+                },
+                "notBefore": { "type": "integer", "format": "int32" },
+                "expiration": { "type": "integer", "format": "int64" },
+                "lastAccess": {
+                    "type": "integer",
+                    "format": "int64",
+                    "description": "When the user last logged in",
+                    "x-unit": "epoch-millis"
+                },
+                "modifiedTimestamp": { "type": "integer", "format": "int32" },
+                "time": { "type": "string", "enum": ["now"] },
+                "username": { "type": "string" }
+            })
+        );
+        let epoch_millis = json!({
+            "type": "integer",
+            "format": "int64",
+            "description": "Milliseconds since the Unix epoch.",
+            "x-unit": "epoch-millis"
+        });
+        let events = &spec["paths"]["/{realm}/events"];
+        assert_eq!(
+            events["get"]["responses"]["200"]["content"]["application/json"]["schema"]
+                ["properties"]["time"],
+            epoch_millis
+        );
+        assert_eq!(
+            events["post"]["requestBody"]["content"]["application/json"]["schema"]["properties"]
+                ["createdTimestamp"],
+            epoch_millis
+        );
+        let parameters = &events["get"]["parameters"];
+        assert_eq!(
+            parameters[0]["schema"],
+            json!({ "type": "string", "format": "date" })
+        );
+        assert_eq!(parameters[1]["schema"]["format"], "date-time");
+        assert_eq!(parameters[2]["schema"].get("x-unit"), None);
+    }
+
+    #[test]
+    fn marks_the_documentation() {
+        let options = Options {
+            timestamp_rules: default_rules(),
+            ..Default::default()
+        };
+        let spec = serde_json::to_value(transform(HTML, &options).unwrap().spec).unwrap();
+
+        let user = &spec["components"]["schemas"]["UserRepresentation"]["properties"];
+        assert_eq!(user["createdTimestamp"]["x-unit"], "epoch-millis");
+        assert_eq!(
+            user["createdTimestamp"]["description"],
+            "Milliseconds since the Unix epoch."
+        );
+        assert_eq!(user["notBefore"].get("x-unit"), None);
+        let parameters = spec["paths"]["/{realm}/events"]["get"]["parameters"]
+            .as_array()
+            .unwrap();
+        for name in ["dateFrom", "dateTo"].iter() {
+            let parameter = parameters
+                .iter()
+                .find(|parameter| parameter["name"] == *name)
+                .unwrap();
+            assert_eq!(parameter["schema"]["format"], "date", "{}", name);
+        }
+    }
+}
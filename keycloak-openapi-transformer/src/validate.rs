@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use openapiv3::{OpenAPI, Operation, Parameter, ReferenceOr};
+
+use crate::diagnostics::{Diagnostics, Location};
+use crate::error::TransformError;
+use crate::paths;
+use crate::report::schema_refs;
+
+/// Check the definition against the rules of OpenAPI a transform can break
+///
+/// These describe the definition rather than any one document, so they are
+/// only meaningful once every document has been merged.
+pub fn validate(spec: &OpenAPI, diagnostics: &mut Diagnostics) {
+    for schema in schema_refs(spec).into_keys() {
+        if !spec
+            .components
+            .iter()
+            .any(|components| components.schemas.contains_key(&schema))
+        {
+            diagnostics.invalid(
+                Default::default(),
+                TransformError::UnresolvedReference(schema),
+            );
+        }
+    }
+
+    let mut operation_ids: BTreeMap<&str, Vec<&Operation>> = BTreeMap::new();
+    for (path, path_item) in spec.paths.paths.iter() {
+        let path_item = match path_item {
+            ReferenceOr::Item(path_item) => path_item,
+            ReferenceOr::Reference { .. } => continue,
+        };
+        let operations: Vec<&Operation> = paths::operations(path_item).collect();
+
+        for operation in operations.iter() {
+            if let Some(id) = &operation.operation_id {
+                operation_ids.entry(id).or_default().push(operation);
+            }
+        }
+
+        for parameter in template_parameters(path) {
+            let undeclared = operations.iter().find(|operation| {
+                !declares(&path_item.parameters, parameter)
+                    && !declares(&operation.parameters, parameter)
+            });
+            if let Some(operation) = undeclared {
+                diagnostics.invalid(
+                    Location::source(&operation.extensions),
+                    TransformError::UndeclaredPathParameter {
+                        path: path.clone(),
+                        parameter: parameter.to_string(),
+                    },
+                );
+            }
+        }
+    }
+
+    for (id, operations) in operation_ids {
+        if let Some(duplicate) = operations.get(1) {
+            diagnostics.invalid(
+                Location::source(&duplicate.extensions),
+                TransformError::DuplicateOperationId(id.to_string()),
+            );
+        }
+    }
+}
+
+/// The names of the parameters in a path template, such as `realm` in `/{realm}/users`
+fn template_parameters(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+fn declares(parameters: &[ReferenceOr<Parameter>], name: &str) -> bool {
+    parameters.iter().any(|parameter| match parameter {
+        ReferenceOr::Item(Parameter::Path { parameter_data, .. }) => parameter_data.name == name,
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use crate::diagnostics::{Diagnostics, Severity};
+    use crate::error::TransformError;
+    use openapiv3::OpenAPI;
+
+    #[test]
+    fn reports_what_openapi_does_not_allow() {
+        let spec: OpenAPI = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.2",
+            "info": { "title": "Keycloak Admin REST API", "version": "1" },
+            "paths": {
+                "/{realm}/users": {
+                    "get": {
+                        "operationId": "getUsers",
+                        "responses": {
+                            "200": {
+                                "description": "",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/UserRepresentation" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/{realm}/users/count": {
+                    "get": { "operationId": "getUsers", "responses": {} }
+                }
+            }
+        }))
+        .unwrap();
+        let mut diagnostics = Diagnostics::default();
+        validate(&spec, &mut diagnostics);
+
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Invalid));
+        assert_eq!(
+            diagnostics.iter().map(|d| &d.error).collect::<Vec<_>>(),
+            vec![
+                &TransformError::UnresolvedReference("UserRepresentation".into()),
+                &TransformError::UndeclaredPathParameter {
+                    path: "/{realm}/users".into(),
+                    parameter: "realm".into()
+                },
+                &TransformError::UndeclaredPathParameter {
+                    path: "/{realm}/users/count".into(),
+                    parameter: "realm".into()
+                },
+                &TransformError::DuplicateOperationId("getUsers".into()),
+            ]
+        );
+    }
+}
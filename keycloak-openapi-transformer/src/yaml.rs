@@ -0,0 +1,399 @@
+//! A YAML emitter producing the same layout as `yq --output-format=yaml -P`
+//!
+//! Keys keep their serialization order, sequences are indented beneath their
+//! key and scalars are only quoted when a plain scalar would be misread. This
+//! keeps the `.yml` definitions byte-for-byte the same as when they were
+//! converted from the `.json` definitions by `yq`.
+//!
+//! YAML is read with [`from_str`], which keeps the order of keys too.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use yaml_rust::{Yaml, YamlLoader};
+
+const INDENT: usize = 2;
+
+lazy_static! {
+    static ref TIMESTAMP_REGEX: Regex = Regex::new(
+        r"^[0-9]{4}-[0-9]{1,2}-[0-9]{1,2}([Tt ][0-9]{1,2}:[0-9]{1,2}:[0-9]{1,2}(\.[0-9]*)?(Z|[+-][0-9]{1,2}:[0-9]{2})?)?$"
+    )
+    .unwrap();
+    static ref FLOAT_REGEX: Regex =
+        Regex::new(r"^[-+]?(\.[0-9]+|[0-9]+(\.[0-9]*)?)([eE][-+]?[0-9]+)?$").unwrap();
+}
+
+/// Serialize the given data structure as a String of YAML
+pub fn to_string<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    let value = serde_json::to_value(value)?;
+    let mut out = String::new();
+    match &value {
+        Value::Object(map) if !map.is_empty() => write_mapping(&mut out, map, 0),
+        Value::Array(seq) if !seq.is_empty() => write_sequence(&mut out, seq, 0),
+        scalar => {
+            write_inline(&mut out, scalar, 0);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+fn write_mapping(out: &mut String, map: &serde_json::Map<String, Value>, indent: usize) {
+    for (index, (key, value)) in map.iter().enumerate() {
+        // The first key of a mapping inside a sequence shares the line with the dash
+        if index > 0 || out.is_empty() || out.ends_with('\n') {
+            push_indent(out, indent);
+        }
+        out.push_str(&scalar(key, true));
+        out.push(':');
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                out.push('\n');
+                write_mapping(out, map, indent + INDENT);
+            }
+            Value::Array(seq) if !seq.is_empty() => {
+                out.push('\n');
+                write_sequence(out, seq, indent + INDENT);
+            }
+            value => {
+                out.push(' ');
+                write_inline(out, value, indent);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn write_sequence(out: &mut String, seq: &[Value], indent: usize) {
+    for (index, value) in seq.iter().enumerate() {
+        if index > 0 || out.is_empty() || out.ends_with('\n') {
+            push_indent(out, indent);
+        }
+        out.push_str("- ");
+        match value {
+            Value::Object(map) if !map.is_empty() => write_mapping(out, map, indent + INDENT),
+            Value::Array(seq) if !seq.is_empty() => write_sequence(out, seq, indent + INDENT),
+            value => {
+                write_inline(out, value, indent);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// Write a value that fits after a key or dash: a scalar or an empty collection
+fn write_inline(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::String(s) if s.contains('\n') && literal_allowed(s) => {
+            write_literal(out, s, indent + INDENT)
+        }
+        Value::String(s) => out.push_str(&scalar(s, false)),
+        Value::Array(_) => out.push_str("[]"),
+        Value::Object(_) => out.push_str("{}"),
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.push_str(&" ".repeat(indent));
+}
+
+fn write_literal(out: &mut String, s: &str, indent: usize) {
+    out.push('|');
+    if s.starts_with(' ') || s.starts_with('\n') {
+        out.push_str(&INDENT.to_string());
+    }
+    if !s.ends_with('\n') {
+        out.push('-');
+    } else if s.len() == 1 || s.ends_with("\n\n") {
+        out.push('+');
+    }
+    out.push('\n');
+    for line in s.strip_suffix('\n').unwrap_or(s).split('\n') {
+        if !line.is_empty() {
+            push_indent(out, indent);
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    // Remove the line break that the caller adds after every value
+    out.pop();
+}
+
+/// Represent a string as a plain, single quoted or double quoted scalar
+fn scalar(s: &str, simple_key: bool) -> String {
+    let analysis = Analysis::new(s);
+    if !resolves_to_string(s) || (simple_key && s.contains('\n')) {
+        double_quoted(s)
+    } else if analysis.plain_allowed && !s.is_empty() {
+        s.to_string()
+    } else if analysis.single_quoted_allowed {
+        format!("'{}'", s.replace('\'', "''"))
+    } else {
+        double_quoted(s)
+    }
+}
+
+fn literal_allowed(s: &str) -> bool {
+    Analysis::new(s).block_allowed
+}
+
+/// Whether YAML 1.2 core schema resolution keeps the plain scalar a string
+fn resolves_to_string(s: &str) -> bool {
+    match s {
+        "" | "~" | "null" | "Null" | "NULL" | "true" | "True" | "TRUE" | "false" | "False"
+        | "FALSE" | ".nan" | ".NaN" | ".NAN" | ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf"
+        | "+.INF" | "-.inf" | "-.Inf" | "-.INF" | "<<" => return false,
+        _ => {}
+    }
+    match s.as_bytes()[0] {
+        b'.' => s.parse::<f64>().is_err(),
+        b'+' | b'-' | b'0'..=b'9' => {
+            let plain = s.replace('_', "");
+            !(TIMESTAMP_REGEX.is_match(s) || is_integer(&plain) || FLOAT_REGEX.is_match(&plain))
+        }
+        _ => true,
+    }
+}
+
+/// Mirrors Go's `strconv.ParseInt(s, 0, 64)` prefix handling
+fn is_integer(s: &str) -> bool {
+    let unsigned = s
+        .strip_prefix('+')
+        .or_else(|| s.strip_prefix('-'))
+        .unwrap_or(s);
+    let lower = unsigned.to_ascii_lowercase();
+    let (digits, radix) = if let Some(hex) = lower.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(binary) = lower.strip_prefix("0b") {
+        (binary, 2)
+    } else if let Some(octal) = lower.strip_prefix("0o") {
+        (octal, 8)
+    } else if lower.len() > 1 && lower.starts_with('0') {
+        (&lower[1..], 8)
+    } else {
+        (lower.as_str(), 10)
+    };
+    !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix))
+}
+
+fn is_printable(c: char) -> bool {
+    matches!(c,
+        '\n'
+        | '\u{20}'..='\u{7E}'
+        | '\u{85}'
+        | '\u{A0}'..='\u{D7FF}'
+        | '\u{E000}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{10FFFF}')
+        && c != '\u{FEFF}'
+}
+
+/// The scalar styles that can faithfully represent a string, as decided by libyaml
+struct Analysis {
+    plain_allowed: bool,
+    single_quoted_allowed: bool,
+    block_allowed: bool,
+}
+
+impl Analysis {
+    fn new(s: &str) -> Self {
+        if s.is_empty() {
+            return Self {
+                plain_allowed: true,
+                single_quoted_allowed: true,
+                block_allowed: false,
+            };
+        }
+
+        let chars: Vec<char> = s.chars().collect();
+        let mut indicators = s.starts_with("---") || s.starts_with("...");
+        let mut line_breaks = false;
+        let mut special_characters = false;
+        let mut tab_characters = false;
+        let mut break_space = false;
+        let mut space_break = false;
+        let mut previous_space = false;
+        let mut previous_break = false;
+        let mut preceded_by_whitespace = true;
+
+        for (i, &c) in chars.iter().enumerate() {
+            let followed_by_whitespace = matches!(chars.get(i + 1), None | Some(' ') | Some('\t'));
+
+            if i == 0 {
+                match c {
+                    '#' | ',' | '[' | ']' | '{' | '}' | '&' | '*' | '!' | '|' | '>' | '\''
+                    | '"' | '%' | '@' | '`' => indicators = true,
+                    '?' | ':' | '-' if followed_by_whitespace => indicators = true,
+                    _ => {}
+                }
+            } else {
+                match c {
+                    ':' if followed_by_whitespace => indicators = true,
+                    '#' if preceded_by_whitespace => indicators = true,
+                    _ => {}
+                }
+            }
+
+            if c == '\t' {
+                tab_characters = true;
+            } else if !is_printable(c) {
+                special_characters = true;
+            }
+
+            if c == ' ' {
+                if previous_break {
+                    break_space = true;
+                }
+                previous_space = true;
+                previous_break = false;
+            } else if c == '\n' {
+                line_breaks = true;
+                if previous_space {
+                    space_break = true;
+                }
+                previous_space = false;
+                previous_break = true;
+            } else {
+                previous_space = false;
+                previous_break = false;
+            }
+
+            preceded_by_whitespace = c == ' ' || c == '\t' || c == '\n';
+        }
+
+        let leading_space = chars[0] == ' ' || chars[0] == '\n';
+        let trailing_space = chars[chars.len() - 1] == ' ';
+        let trailing_break = chars[chars.len() - 1] == '\n';
+
+        Self {
+            plain_allowed: !(leading_space
+                || trailing_space
+                || trailing_break
+                || break_space
+                || space_break
+                || tab_characters
+                || special_characters
+                || line_breaks
+                || indicators),
+            single_quoted_allowed: !(break_space
+                || space_break
+                || tab_characters
+                || special_characters),
+            block_allowed: !(trailing_space || space_break || special_characters),
+        }
+    }
+}
+
+fn double_quoted(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\0' => out.push_str("\\0"),
+            '\u{7}' => out.push_str("\\a"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{B}' => out.push_str("\\v"),
+            '\u{C}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            '\u{1B}' => out.push_str("\\e"),
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{85}' => out.push_str("\\N"),
+            '\u{A0}' => out.push_str("\\_"),
+            '\u{2028}' => out.push_str("\\L"),
+            '\u{2029}' => out.push_str("\\P"),
+            c if is_printable(c) => out.push(c),
+            c if (c as u32) <= 0xFF => out.push_str(&format!("\\x{:02X}", c as u32)),
+            c if (c as u32) <= 0xFFFF => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push_str(&format!("\\U{:08X}", c as u32)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Read the first document of a YAML stream as JSON
+pub fn from_str(yaml: &str) -> Result<Value, String> {
+    YamlLoader::load_from_str(yaml)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .map_or(Ok(Value::Null), to_json)
+}
+
+fn to_json(yaml: Yaml) -> Result<Value, String> {
+    Ok(match yaml {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(boolean) => Value::Bool(boolean),
+        Yaml::Integer(integer) => integer.into(),
+        Yaml::Real(real) => real
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("{} is not a JSON number", real))?,
+        Yaml::String(string) => Value::String(string),
+        Yaml::Array(array) => {
+            Value::Array(array.into_iter().map(to_json).collect::<Result<_, _>>()?)
+        }
+        Yaml::Hash(hash) => Value::Object(
+            hash.into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        Yaml::String(key) | Yaml::Real(key) => key,
+                        Yaml::Integer(key) => key.to_string(),
+                        Yaml::Boolean(key) => key.to_string(),
+                        key => return Err(format!("unsupported mapping key {:?}", key)),
+                    };
+                    Ok((key, to_json(value)?))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        Yaml::Alias(_) => return Err("aliases are not supported".into()),
+        Yaml::BadValue => return Err("invalid value".into()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_str, to_string};
+    use serde_json::Value;
+
+    #[test]
+    fn matches_yq_output() {
+        const JSON: &str = include_str!("../../keycloak/23.0.7.json");
+        const YAML: &str = include_str!("../../keycloak/23.0.7.yml");
+        let value: Value = serde_json::from_str(JSON).unwrap();
+
+        assert_eq!(to_string(&value).unwrap(), YAML);
+    }
+
+    #[test]
+    fn reads_what_it_writes() {
+        const JSON: &str = include_str!("../../keycloak/23.0.7.json");
+        const YAML: &str = include_str!("../../keycloak/23.0.7.yml");
+        let value: Value = serde_json::from_str(JSON).unwrap();
+
+        assert_eq!(from_str(YAML).unwrap(), value);
+    }
+
+    #[test]
+    fn quotes_scalars_that_would_change_type() {
+        let value = serde_json::json!({
+            "version": "1.0",
+            "200": "true",
+            "empty": "",
+            "ref": "#/components/schemas/Thing",
+            "plain": "Get the realm",
+        });
+
+        assert_eq!(
+            to_string(&value).unwrap(),
+            "version: \"1.0\"\n\"200\": \"true\"\nempty: \"\"\nref: '#/components/schemas/Thing'\nplain: Get the realm\n"
+        );
+    }
+}